@@ -1,65 +1,275 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
+use anchor_lang::solana_program::hash::{hash, hashv};
+use anchor_lang::system_program;
+use std::collections::BTreeMap;
+use bytemuck::Zeroable;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
 
-declare_id!("PROGRAM_ID_PLACEHOLDER");
+declare_id!("8hdKSp4hBqQH1mcftKx8fgqe3fXS3WujqpFZpFu1F8au");
 
 // ─── Constants ────────────────────────────────────────────────────────────────
-const MAX_GRID_TILES: usize = 100; // 10×10
-const MAX_PLAYERS: usize = 10;
+const MAX_GRID_SIZE: usize = 64;
+const MAX_GRID_TILES: usize = MAX_GRID_SIZE * MAX_GRID_SIZE;
+const GRID_WORDS: usize = MAX_GRID_TILES.div_ceil(64); // one bit per tile
+const MAX_PLAYERS: usize = 64;
+const MAX_FEE_BPS: u16 = 10_000; // 100%
+const MAX_AUTHORITIES: usize = 16;
+const MAX_MINTS: usize = 8;
+
+// max_players, player_count, width and height are stored as u8.
+const _: () = assert!(MAX_PLAYERS <= u8::MAX as usize);
+const _: () = assert!(MAX_GRID_SIZE <= u8::MAX as usize);
 
 // ─── Program ──────────────────────────────────────────────────────────────────
 #[program]
 pub mod ignite {
     use super::*;
 
-    /// Initialize a new game: create GameState and EscrowVault PDAs.
+    /// Create the global Config PDA. The first caller becomes its admin,
+    /// so this should run as part of deployment.
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.paused = false;
+        config.apply(params)
+    }
+
+    /// Admin-only: replace every Config setting. Rake changes only affect
+    /// games created afterwards.
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        ctx.accounts.config.apply(params)
+    }
+
+    /// Admin-only: stop (or resume) new games, joins and gameplay. Refunds
+    /// via `leave_game`/`cancel_game`/`pay_out` stay open, and
+    /// `emergency_refund` becomes available. Resuming restarts every
+    /// game's inactivity clock, since authorities couldn't act meanwhile.
+    pub fn set_paused(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.paused = paused;
+        if !paused {
+            config.unpaused_at = Clock::get()?.unix_timestamp;
+        }
+
+        emit!(ProgramPaused { paused });
+        Ok(())
+    }
+
+    /// Admin-only: hand the admin role to `new_admin`, who must co-sign so
+    /// the role can't be sent to a key nobody controls.
+    pub fn rotate_admin(ctx: Context<RotateAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let old_admin = config.admin;
+        config.admin = ctx.accounts.new_admin.key();
+
+        emit!(AdminRotated {
+            old_admin,
+            new_admin: config.admin,
+        });
+        Ok(())
+    }
+
+    /// Authority-only: create, or grow, the GameState PDA for `game_id`
+    /// ahead of `initialize_game`. Accounts made by CPI start at 10 KiB at
+    /// most and grow by at most 10 KiB per instruction, so a layout larger
+    /// than that takes several calls; they can share one transaction.
+    pub fn allocate_game(ctx: Context<AllocateGame>, game_id: [u8; 16]) -> Result<()> {
+        let config = &ctx.accounts.config;
+        require!(!config.paused, IgniteError::ProgramPaused);
+        require!(
+            config.authorities.contains(&ctx.accounts.authority.key()),
+            IgniteError::UnapprovedAuthority
+        );
+
+        let info = ctx.accounts.game_state.to_account_info();
+        let len = info.data_len();
+        require!(len < GameState::SIZE, IgniteError::GameAlreadyAllocated);
+        let target = GameState::SIZE.min(len + MAX_PERMITTED_DATA_INCREASE);
+
+        let rent = Rent::get()?
+            .minimum_balance(target)
+            .saturating_sub(info.lamports());
+        if rent > 0 {
+            let transfer_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: info.clone(),
+                },
+            );
+            system_program::transfer(transfer_ctx, rent)?;
+        }
+
+        if len > 0 {
+            info.realloc(target, true)?;
+            return Ok(());
+        }
+        // First call: the PDA signs for its own allocation and assignment
+        let bump = [ctx.bumps.game_state];
+        let seeds = &[b"game_state".as_ref(), game_id.as_ref(), &bump];
+        let signer = &[&seeds[..]];
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Allocate {
+                    account_to_allocate: info.clone(),
+                },
+                signer,
+            ),
+            target as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Assign {
+                    account_to_assign: info,
+                },
+                signer,
+            ),
+            &crate::ID,
+        )
+    }
+    /// Initialize a new game: fill in the GameState PDA sized by
+    /// `allocate_game`, and create the EscrowVault PDA.
+    /// The escrow vault is a token account for `mint` whose authority is
+    /// the vault PDA itself, so only this program can move funds out of it.
     /// Only the authority (Ignite server keypair) can call this.
     pub fn initialize_game(
         ctx: Context<InitializeGame>,
         game_id: [u8; 16],
-        buy_in: u64,
-        grid_size: u8,
+        params: InitializeGameParams,
     ) -> Result<()> {
-        require!(grid_size <= 10, IgniteError::InvalidGridSize);
-        let game = &mut ctx.accounts.game_state;
+        let config = &ctx.accounts.config;
+        require!(!config.paused, IgniteError::ProgramPaused);
+        require!(
+            config.authorities.contains(&ctx.accounts.authority.key()),
+            IgniteError::UnapprovedAuthority
+        );
+        require!(
+            config.allowed_mints.contains(&ctx.accounts.mint.key()),
+            IgniteError::MintNotAllowed
+        );
+        require!(
+            params.buy_in >= config.min_buy_in && params.buy_in <= config.max_buy_in,
+            IgniteError::BuyInOutOfRange
+        );
+
+        let sides = 1..=MAX_GRID_SIZE as u8;
+        require!(
+            sides.contains(&params.width) && sides.contains(&params.height),
+            IgniteError::InvalidGridSize
+        );
+        require!(
+            params.min_players >= 2
+                && params.min_players <= params.max_players
+                && params.max_players as usize <= MAX_PLAYERS,
+            IgniteError::InvalidPlayerLimits
+        );
+        // A zero timeout would let anyone cancel, start or expire at will
+        require!(
+            params.cancel_timeout > 0 && params.lobby_timeout > 0 && params.inactivity_timeout > 0,
+            IgniteError::InvalidTimeout
+        );
+        // Only the random pattern draws on the seed, and it needs one. An
+        // all-zero commitment is how GameState spells "none", so refuse it.
+        let random = matches!(params.collapse_pattern, CollapsePattern::Random { .. });
+        let seeded = params.seed_commitment.is_some_and(|c| c != [0; 32]);
+        require!(seeded == random, IgniteError::InvalidCollapsePattern);
+        require!(
+            params.collapse_pattern != CollapsePattern::Random { count: 0 },
+            IgniteError::InvalidCollapseCount
+        );
+
+        // The account arrives zeroed: no players, no lava, empty pot
+        let game = &mut *ctx.accounts.game_state.load_init()?;
         game.game_id = game_id;
         game.authority = ctx.accounts.authority.key();
-        game.status = 0; // waiting
-        game.grid_size = grid_size;
-        // Initialize grid: all safe (0)
-        game.grid = vec![0u8; (grid_size as usize) * (grid_size as usize)];
-        game.players = vec![];
-        game.buy_in = buy_in;
-        game.prize_pool = 0;
-        game.winner = None;
+        game.mint = ctx.accounts.mint.key();
+        game.status = GameStatus::Waiting.into();
+        game.width = params.width;
+        game.height = params.height;
+        if let Some(mask) = &params.mask {
+            game.block_tiles(mask)?;
+        }
+        game.buy_in = params.buy_in;
         game.created_at = Clock::get()?.unix_timestamp;
-        game.collapse_round = 0;
+        game.last_action_at = game.created_at;
+        game.require_cosign = params.require_cosign as u8;
+        game.cancel_timeout = params.cancel_timeout;
+        game.min_players = params.min_players;
+        game.max_players = params.max_players;
+        game.lobby_timeout = params.lobby_timeout;
+        game.inactivity_timeout = params.inactivity_timeout;
+        game.seed_commitment = params.seed_commitment.unwrap_or_default();
+        game.collapse_pattern = params.collapse_pattern.into();
+        game.forfeit_rule = params.forfeit_rule.into();
+        game.sealed_moves = params.sealed_moves as u8;
+        game.rent_receiver = params.rent_receiver.unwrap_or(game.authority);
+        // Players join under the rake in force when the game was created
+        game.fee_bps = ctx.accounts.config.fee_bps;
+
+        emit!(GameCreated {
+            game_id,
+            authority: game.authority,
+            mint: game.mint,
+            buy_in: game.buy_in,
+            width: game.width,
+            height: game.height,
+            mask: params.mask,
+            min_players: game.min_players,
+            max_players: game.max_players,
+            seed_commitment: game.seed_commitment(),
+            collapse_pattern: params.collapse_pattern,
+            sealed_moves: params.sealed_moves,
+        });
         Ok(())
     }
 
     /// Player joins a game by transferring USDC to the escrow vault.
+    /// The roster entry is the paying wallet, or an optional session key
+    /// that co-signs the join and then signs moves on the wallet's behalf.
+    /// Seeded games also take `sha256(entropy)`, revealed with
+    /// `reveal_entropy` once `lock_lobby` has closed joins.
     pub fn join_game(
         ctx: Context<JoinGame>,
-        game_id: [u8; 16],
-        player_pubkey: Pubkey,
+        _game_id: [u8; 16],
         start_x: u8,
         start_y: u8,
+        entropy_commitment: Option<[u8; 32]>,
     ) -> Result<()> {
-        let game = &mut ctx.accounts.game_state;
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        let owner = ctx.accounts.player.key();
+        let player_pubkey = ctx
+            .accounts
+            .session_key
+            .as_ref()
+            .map_or(owner, |k| k.key());
+
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Waiting, IgniteError::GameNotJoinable);
+        require!(game.player_count < game.max_players, IgniteError::GameFull);
+        require!(
+            game.seed_commitment().is_none() || entropy_commitment.is_some(),
+            IgniteError::EntropyCommitmentRequired
+        );
 
-        require!(game.status == 0, IgniteError::GameNotJoinable);
+        // One slot per wallet and per signing key
         require!(
-            game.players.len() < MAX_PLAYERS,
-            IgniteError::GameFull
+            !game
+                .players()
+                .iter()
+                .any(|p| p.pubkey == player_pubkey || p.owner == owner),
+            IgniteError::AlreadyJoined
         );
 
         // Ensure starting tile is safe
-        let tile_idx = (start_y as usize) * (game.grid_size as usize) + (start_x as usize);
-        require!(tile_idx < game.grid.len(), IgniteError::OutOfBounds);
-        require!(game.grid[tile_idx] == 0, IgniteError::TileIsLava);
+        let tile = game.tile(start_x, start_y).ok_or(IgniteError::OutOfBounds)?;
+        require!(!game.is_blocked(tile), IgniteError::TileNotPlayable);
+        require!(!game.is_lava(tile), IgniteError::TileIsLava);
 
         // Ensure spot not occupied
-        for p in &game.players {
+        for p in game.players() {
             require!(
                 !(p.x == start_x && p.y == start_y),
                 IgniteError::TileOccupied
@@ -77,227 +287,1895 @@ pub mod ignite {
         );
         token::transfer(transfer_ctx, game.buy_in)?;
 
-        game.players.push(PlayerState {
+        game.add_player(PlayerState {
             pubkey: player_pubkey,
+            owner,
+            entropy_commitment: entropy_commitment.unwrap_or_default(),
+            entropy: [0; 32],
             x: start_x,
             y: start_y,
-            alive: true,
-        });
+            alive: 1,
+            eliminated_in: 0,
+            revealed: 0,
+            moved: 0,
+            move_commitment: [0; 32],
+            direction: 0,
+            move_revealed: 0,
+            paid_out: 0,
+            forfeited: 0,
+        })?;
         game.prize_pool = game.prize_pool.checked_add(game.buy_in).unwrap();
 
-        // Auto-activate if game has players (for MVP; in prod use max_players)
-        if game.players.len() >= 2 {
-            game.status = 1; // active
+        emit!(PlayerJoined {
+            game_id: game.game_id,
+            player: player_pubkey,
+            owner,
+            x: start_x,
+            y: start_y,
+            prize_pool: game.prize_pool,
+        });
+        Ok(())
+    }
+
+    /// Close a seeded game's lobby so players can reveal their entropy.
+    /// Nobody may join or leave afterwards, so no one can choose to play
+    /// after seeing others' entropy. The authority may lock it once
+    /// `min_players` have joined; anyone else may once `lobby_timeout`
+    /// seconds have passed since `created_at`.
+    pub fn lock_lobby(ctx: Context<LockLobby>, game_id: [u8; 16]) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.seed_commitment().is_some(), IgniteError::NoSeedCommitted);
+
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.caller.key() != game.authority {
+            let deadline = game.created_at.checked_add(game.lobby_timeout).unwrap();
+            require!(now >= deadline, IgniteError::LobbyTimeoutNotReached);
+        }
+        require!(
+            game.player_count >= game.min_players,
+            IgniteError::NotEnoughPlayers
+        );
+
+        game.transition(GameStatus::Revealing)?;
+        game.last_action_at = now;
+
+        emit!(LobbyLocked {
+            game_id,
+            player_count: game.player_count,
+        });
+        Ok(())
+    }
+
+    /// Player reveals the entropy committed to at `join_game`. Either the
+    /// paying wallet or its session key may sign. Only between `lock_lobby`
+    /// and `start_game`, so every value is fixed before the authority
+    /// reveals its seed and no one can join after seeing it.
+    pub fn reveal_entropy(
+        ctx: Context<RevealEntropy>,
+        game_id: [u8; 16],
+        entropy: [u8; 32],
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.seed_commitment().is_some(), IgniteError::NoSeedCommitted);
+        require!(
+            game.status() == GameStatus::Revealing,
+            IgniteError::EntropyRevealsClosed
+        );
+
+        let signer = ctx.accounts.player.key();
+        let player = game
+            .players_mut()
+            .iter_mut()
+            .find(|p| p.pubkey == signer || p.owner == signer)
+            .ok_or(IgniteError::PlayerNotInGame)?;
+        require!(player.entropy().is_none(), IgniteError::EntropyAlreadyRevealed);
+        require!(
+            player.entropy_commitment == hash(&entropy).to_bytes(),
+            IgniteError::EntropyMismatch
+        );
+        player.entropy = entropy;
+        player.revealed = 1;
+
+        emit!(EntropyRevealed {
+            game_id,
+            player: player.pubkey,
+            entropy,
+        });
+        Ok(())
+    }
+
+    /// Start a waiting game once at least `min_players` are in it.
+    /// The authority may start it at any time; anyone else may once
+    /// `lobby_timeout` seconds have passed since `created_at`.
+    /// Seeded games start from `lock_lobby` instead, and anyone else must
+    /// wait `lobby_timeout` seconds from the lock, giving players that long
+    /// to reveal. Players who haven't revealed their entropy forfeit
+    /// according to `forfeit_rule` first, and only the rest count.
+    /// Remaining accounts: under `ForfeitRule::Seat`, a refund token
+    /// account for each of the first forfeiters, in roster order, owned by
+    /// that player's wallet; `pay_out` refunds any that don't fit.
+    pub fn start_game<'info>(
+        ctx: Context<'_, '_, 'info, 'info, StartGame<'info>>,
+        game_id: [u8; 16],
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+
+        let seeded = game.seed_commitment().is_some();
+        let locked = game.status() == GameStatus::Revealing;
+        require!(!seeded || locked, IgniteError::LobbyNotLocked);
+
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.caller.key() != game.authority {
+            let opened = if locked { game.last_action_at } else { game.created_at };
+            let deadline = opened.checked_add(game.lobby_timeout).unwrap();
+            require!(now >= deadline, IgniteError::LobbyTimeoutNotReached);
+        }
+
+        game.transition(GameStatus::Active)?;
+        game.last_action_at = now;
+
+        // Forfeiters stay on the roster, out before the first collapse
+        let mut forfeited = vec![];
+        for p in game.players_mut() {
+            if seeded && p.entropy().is_none() {
+                p.alive = 0;
+                p.forfeited = 1;
+                forfeited.push(p.pubkey);
+            }
         }
+        // Under ForfeitRule::Seat they are the payees until refunded
+        let refunds = game.payees().len() as u64;
+        game.payout = game.seat_refund().checked_mul(refunds).unwrap();
+
+        let playing = game.alive_count();
+        require!(
+            playing >= game.min_players as usize,
+            IgniteError::NotEnoughPlayers
+        );
+
+        emit!(GameStarted {
+            game_id,
+            player_count: playing as u8,
+            forfeited,
+        });
+        pay_payees(
+            game,
+            0,
+            ctx.remaining_accounts,
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.bumps.escrow_vault,
+        )
+    }
+
+    /// Player leaves a waiting game and gets their buy-in back. Either the
+    /// paying wallet or its session key may sign; funds go to the wallet.
+    pub fn leave_game(ctx: Context<LeaveGame>, game_id: [u8; 16]) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(game.status() == GameStatus::Waiting, IgniteError::GameNotJoinable);
 
+        let signer = ctx.accounts.player.key();
+        let idx = game
+            .players()
+            .iter()
+            .position(|p| p.pubkey == signer || p.owner == signer)
+            .ok_or(IgniteError::PlayerNotInGame)?;
+        require_keys_eq!(
+            ctx.accounts.player_token_account.owner,
+            game.players[idx].owner,
+            IgniteError::InvalidRefundAccount
+        );
+
+        transfer_from_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.accounts.player_token_account.to_account_info(),
+            &game_id,
+            ctx.bumps.escrow_vault,
+            game.buy_in,
+        )?;
+
+        let player = game.remove_player(idx);
+        game.prize_pool = game.prize_pool.checked_sub(game.buy_in).unwrap();
+
+        emit!(PlayerLeft {
+            game_id,
+            player: player.pubkey,
+            refund: game.buy_in,
+        });
         Ok(())
     }
 
-    /// Player submits a move. Authority co-signs to validate server-side logic.
+    /// Player submits a move: at most one per player per collapse round.
+    /// In server-mediated games (`require_cosign`) the game authority must
+    /// co-sign to validate server-side logic.
     pub fn submit_move(
         ctx: Context<SubmitMove>,
         _game_id: [u8; 16],
         new_x: u8,
         new_y: u8,
     ) -> Result<()> {
-        let game = &mut ctx.accounts.game_state;
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(!game.has_sealed_moves(), IgniteError::MovesSealed);
+        require!(
+            !game.requires_cosign() || ctx.accounts.authority.is_some(),
+            IgniteError::CosignRequired
+        );
 
-        require!(game.status == 1, IgniteError::GameNotActive);
+        // Validate tile is in bounds and safe
+        let tile = game.tile(new_x, new_y).ok_or(IgniteError::OutOfBounds)?;
+        require!(!game.is_blocked(tile), IgniteError::TileNotPlayable);
+        require!(!game.is_lava(tile), IgniteError::TileIsLava);
 
         let player_key = ctx.accounts.player.key();
         let player_state = game
-            .players
+            .players_mut()
             .iter_mut()
             .find(|p| p.pubkey == player_key)
             .ok_or(IgniteError::PlayerNotInGame)?;
 
-        require!(player_state.alive, IgniteError::PlayerEliminated);
+        require!(player_state.is_alive(), IgniteError::PlayerEliminated);
+        require!(!player_state.has_moved(), IgniteError::AlreadyMoved);
 
         // Validate adjacency (Manhattan distance of 1)
         let dx = (new_x as i16 - player_state.x as i16).abs();
         let dy = (new_y as i16 - player_state.y as i16).abs();
         require!(dx + dy == 1, IgniteError::InvalidMove);
 
-        // Validate tile is safe
-        let tile_idx = (new_y as usize) * (game.grid_size as usize) + (new_x as usize);
-        require!(tile_idx < game.grid.len(), IgniteError::OutOfBounds);
-        require!(game.grid[tile_idx] == 0, IgniteError::TileIsLava);
-
         player_state.x = new_x;
         player_state.y = new_y;
+        player_state.moved = 1;
+
+        emit!(PlayerMoved {
+            game_id: game.game_id,
+            player: player_key,
+            x: new_x,
+            y: new_y,
+            round: game.collapse_round,
+        });
+        Ok(())
+    }
+
+    /// In sealed-move games, a player commits this round's move as
+    /// `move_commitment(direction, salt)`, hiding it until everyone has
+    /// chosen. Same signer, cosign and one-per-round rules as `submit_move`.
+    pub fn commit_move(
+        ctx: Context<SubmitMove>,
+        game_id: [u8; 16],
+        commitment: [u8; 32],
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(game.has_sealed_moves(), IgniteError::MovesNotSealed);
+        require!(game.move_phase() == MovePhase::Commit, IgniteError::WrongMovePhase);
+        require!(
+            !game.requires_cosign() || ctx.accounts.authority.is_some(),
+            IgniteError::CosignRequired
+        );
+
+        let round = game.collapse_round;
+        let player_key = ctx.accounts.player.key();
+        let player_state = game
+            .players_mut()
+            .iter_mut()
+            .find(|p| p.pubkey == player_key)
+            .ok_or(IgniteError::PlayerNotInGame)?;
+
+        require!(player_state.is_alive(), IgniteError::PlayerEliminated);
+        require!(!player_state.has_moved(), IgniteError::AlreadyMoved);
+
+        player_state.move_commitment = commitment;
+        player_state.moved = 1;
+
+        emit!(MoveCommitted {
+            game_id,
+            player: player_key,
+            round,
+        });
+        Ok(())
+    }
+
+    /// Authority-only: close this round's commits so players can reveal.
+    /// Players who haven't committed sit the round out. Needed every round,
+    /// even one with no commits, before the collapse can run.
+    pub fn open_reveals(ctx: Context<TriggerCollapse>, game_id: [u8; 16]) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(game.has_sealed_moves(), IgniteError::MovesNotSealed);
+        require!(game.move_phase() == MovePhase::Commit, IgniteError::WrongMovePhase);
+
+        game.move_phase = MovePhase::Reveal.into();
+        game.last_action_at = Clock::get()?.unix_timestamp;
+
+        emit!(RevealsOpened {
+            game_id,
+            round: game.collapse_round,
+        });
+        Ok(())
+    }
+
+    /// Player reveals the move committed with `commit_move`. Moves take
+    /// effect together at `resolve_moves`; an unrevealed one stays put.
+    pub fn reveal_move(
+        ctx: Context<RevealMove>,
+        game_id: [u8; 16],
+        direction: Direction,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(game.move_phase() == MovePhase::Reveal, IgniteError::WrongMovePhase);
+
+        let player_key = ctx.accounts.player.key();
+        let player_state = game
+            .players_mut()
+            .iter_mut()
+            .find(|p| p.pubkey == player_key)
+            .ok_or(IgniteError::PlayerNotInGame)?;
+
+        require!(player_state.move_commitment != [0; 32], IgniteError::NoMoveCommitted);
+        require!(player_state.revealed_move().is_none(), IgniteError::MoveAlreadyRevealed);
+        require!(
+            player_state.move_commitment == move_commitment(direction, &salt),
+            IgniteError::MoveMismatch
+        );
+        player_state.direction = direction as u8;
+        player_state.move_revealed = 1;
+
+        emit!(MoveRevealed {
+            game_id,
+            player: player_key,
+            direction,
+        });
+        Ok(())
+    }
+
+    /// Authority-only: apply every revealed move at once (see
+    /// `GameState::resolve_moves` for the collision rules). The round's
+    /// collapse can then run, which reopens commits.
+    pub fn resolve_moves(ctx: Context<TriggerCollapse>, game_id: [u8; 16]) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(game.move_phase() == MovePhase::Reveal, IgniteError::WrongMovePhase);
+
+        let before: Vec<(u8, u8)> = game.players().iter().map(|p| (p.x, p.y)).collect();
+        let bounced = game.resolve_moves();
+        game.move_phase = MovePhase::Resolved.into();
+        game.last_action_at = Clock::get()?.unix_timestamp;
+
+        let round = game.collapse_round;
+        for (p, &from) in game.players().iter().zip(&before) {
+            if (p.x, p.y) != from {
+                emit!(PlayerMoved {
+                    game_id,
+                    player: p.pubkey,
+                    x: p.x,
+                    y: p.y,
+                    round,
+                });
+            }
+        }
+        emit!(MovesResolved {
+            game_id,
+            round,
+            bounced,
+        });
+        Ok(())
+    }
 
+    /// Authority-only: publish the seed committed to at `initialize_game`,
+    /// fixing every remaining collapse. Only allowed once the game is active
+    /// so players can't pick start tiles with the schedule in hand.
+    /// The collapse RNG is seeded with `sha256(seed || e_1 || … || e_n)`
+    /// over the entropy revealed by players, in roster order, so neither
+    /// the authority nor any single player can predict it alone.
+    pub fn reveal_seed(ctx: Context<RevealSeed>, _game_id: [u8; 16], seed: [u8; 32]) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        let commitment = game.seed_commitment().ok_or(IgniteError::NoSeedCommitted)?;
+        require!(game.seed().is_none(), IgniteError::SeedAlreadyRevealed);
+        require!(
+            hash(&seed).to_bytes() == commitment,
+            IgniteError::SeedMismatch
+        );
+        let entropy: Vec<[u8; 32]> = game.players().iter().filter_map(|p| p.entropy()).collect();
+        let mut parts: Vec<&[u8]> = vec![&seed];
+        parts.extend(entropy.iter().map(|e| &e[..]));
+        game.seed = hashv(&parts).to_bytes();
+        game.last_action_at = Clock::get()?.unix_timestamp;
+
+        emit!(SeedRevealed {
+            game_id: game.game_id,
+            seed,
+        });
         Ok(())
     }
 
-    /// Authority-only: collapse specified tiles and eliminate players on them.
+    /// Authority-only: start the specified tiles cracking. Last round's
+    /// cracking tiles turn to lava, eliminating the players left on them.
+    /// Games with a `collapse_pattern` other than `Manual` only accept the
+    /// tiles the pattern gives for this round (see `advance_collapse`).
+    /// Refused once fewer than two players are alive, so the final round
+    /// stays the one that decided the game, and in sealed-move games until
+    /// `resolve_moves` has run for the round.
     pub fn trigger_collapse(
         ctx: Context<TriggerCollapse>,
         _game_id: [u8; 16],
         tiles: Vec<(u8, u8)>,
     ) -> Result<()> {
-        let game = &mut ctx.accounts.game_state;
-        require!(game.status == 1, IgniteError::GameNotActive);
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(game.ready_to_collapse(), IgniteError::WrongMovePhase);
+        require!(game.alive_count() >= 2, IgniteError::GameAlreadyDecided);
 
-        for (tx, ty) in &tiles {
-            let idx = (*ty as usize) * (game.grid_size as usize) + (*tx as usize);
-            if idx < game.grid.len() {
-                game.grid[idx] = 1; // lava
-            }
+        let round = game.collapse_round.checked_add(1).unwrap();
+        if game.collapse_pattern() != CollapsePattern::Manual {
+            let expected = game.scheduled_collapse(round)?;
+            require!(tiles == expected, IgniteError::CollapseScheduleMismatch);
         }
 
-        // Eliminate players on lava tiles
-        for p in game.players.iter_mut() {
-            if p.alive {
-                let idx = (p.y as usize) * (game.grid_size as usize) + (p.x as usize);
-                if game.grid[idx] == 1 {
-                    p.alive = false;
-                }
-            }
-        }
+        let eliminated = game.collapse(&tiles, round);
+        game.last_action_at = Clock::get()?.unix_timestamp;
 
-        game.collapse_round = game.collapse_round.checked_add(1).unwrap();
+        emit!(TilesCollapsed {
+            game_id: game.game_id,
+            round,
+            tiles,
+            eliminated,
+        });
+        Ok(())
+    }
+
+    /// Authority-only: collapse the next round of the game's
+    /// `collapse_pattern`, computed on-chain. Any client can predict the
+    /// same tiles with `GameState::scheduled_collapse`. Refused in the
+    /// same cases as `trigger_collapse`.
+    pub fn advance_collapse(ctx: Context<TriggerCollapse>, _game_id: [u8; 16]) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        require!(game.status() == GameStatus::Active, IgniteError::GameNotActive);
+        require!(game.ready_to_collapse(), IgniteError::WrongMovePhase);
+        require!(game.alive_count() >= 2, IgniteError::GameAlreadyDecided);
 
+        let round = game.collapse_round.checked_add(1).unwrap();
+        let tiles = game.scheduled_collapse(round)?;
+        let eliminated = game.collapse(&tiles, round);
+        game.last_action_at = Clock::get()?.unix_timestamp;
+
+        emit!(TilesCollapsed {
+            game_id: game.game_id,
+            round,
+            tiles,
+            eliminated,
+        });
         Ok(())
     }
 
     /// Authority-only: declare winner and release escrow to winner's ATA.
     pub fn declare_winner(ctx: Context<DeclareWinner>, game_id: [u8; 16]) -> Result<()> {
-        let game = &mut ctx.accounts.game_state;
-        require!(game.status == 1, IgniteError::GameNotActive);
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        game.transition(GameStatus::Resolved)?;
+        require!(!game.seat_refunds_pending(), IgniteError::SeatRefundsPending);
 
-        let alive: Vec<&PlayerState> = game.players.iter().filter(|p| p.alive).collect();
+        let alive: Vec<&PlayerState> = game.players().iter().filter(|p| p.is_alive()).collect();
         require!(alive.len() == 1, IgniteError::GameNotResolved);
 
         let winner_pubkey = alive[0].pubkey;
-        game.winner = Some(winner_pubkey);
-        game.status = 2; // resolved
+        require_keys_eq!(
+            ctx.accounts.winner_token_account.owner,
+            alive[0].owner,
+            IgniteError::InvalidWinnerAccount
+        );
+        game.winner = winner_pubkey;
+
+        let (fee, payout) = game.split_pot()?;
 
-        // Transfer escrow to winner's token account
-        let seeds = &[
-            b"escrow".as_ref(),
+        if fee > 0 {
+            transfer_from_escrow(
+                &ctx.accounts.token_program,
+                &ctx.accounts.escrow_vault,
+                ctx.accounts.treasury_token_account.to_account_info(),
+                &game_id,
+                ctx.bumps.escrow_vault,
+                fee,
+            )?;
+        }
+        transfer_from_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.accounts.winner_token_account.to_account_info(),
             &game_id,
-            &[ctx.bumps.escrow_vault],
-        ];
-        let signer = &[&seeds[..]];
+            ctx.bumps.escrow_vault,
+            payout,
+        )?;
 
-        let transfer_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow_vault.to_account_info(),
-                to: ctx.accounts.winner_token_account.to_account_info(),
-                authority: ctx.accounts.escrow_vault.to_account_info(),
-            },
-            signer,
-        );
-        token::transfer(transfer_ctx, game.prize_pool)?;
+        emit!(WinnerDeclared {
+            game_id,
+            winner: winner_pubkey,
+            payout,
+            fee,
+        });
+        game.fee_paid = fee;
         game.prize_pool = 0;
 
         Ok(())
     }
-}
 
-// ─── Account Structs ──────────────────────────────────────────────────────────
+    /// Authority-only: resolve a game where the last collapse eliminated
+    /// every remaining player. The pot is split evenly among the players
+    /// eliminated in that final round; any remainder is paid one unit each
+    /// to the earliest of them in roster order.
+    /// Remaining accounts: a destination token account for each of the
+    /// first finalists, in roster order, owned by that player's wallet;
+    /// `pay_out` pays any that don't fit in the transaction.
+    pub fn declare_draw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeclareDraw<'info>>,
+        game_id: [u8; 16],
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        require!(!ctx.accounts.config.paused, IgniteError::ProgramPaused);
+        game.transition(GameStatus::Draw)?;
+        require!(!game.seat_refunds_pending(), IgniteError::SeatRefundsPending);
 
-#[account]
-pub struct GameState {
-    pub game_id: [u8; 16],
-    pub authority: Pubkey,
-    pub status: u8,            // 0=waiting 1=active 2=resolved
-    pub grid_size: u8,
-    pub grid: Vec<u8>,         // flattened grid, 0=safe 1=lava (max 100)
-    pub players: Vec<PlayerState>,
-    pub buy_in: u64,
-    pub prize_pool: u64,
-    pub winner: Option<Pubkey>,
-    pub created_at: i64,
-    pub collapse_round: u8,
-}
+        let finalists = game.finalists()?;
+        game.payout = game.prize_pool;
 
-impl GameState {
-    // 8 (discriminator) + 16 + 32 + 1 + 1
-    // + (4 + MAX_GRID_TILES) + (4 + MAX_PLAYERS * PlayerState::SIZE)
-    // + 8 + 8 + (1 + 32) + 8 + 1 = ~580 bytes → use 1024 for headroom
-    pub const SIZE: usize = 1024;
-}
+        emit!(GameDrawn {
+            game_id,
+            round: game.final_round(),
+            finalists: finalists.iter().map(|&i| game.players[i].pubkey).collect(),
+            prize_pool: game.payout,
+        });
+        pay_payees(
+            game,
+            0,
+            ctx.remaining_accounts,
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.bumps.escrow_vault,
+        )
+    }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct PlayerState {
-    pub pubkey: Pubkey, // 32
-    pub x: u8,         //  1
-    pub y: u8,         //  1
-    pub alive: bool,   //  1
-                       // = 35 bytes each
-}
+    /// End an active game whose authority has stopped running it. Anyone may
+    /// call this once `inactivity_timeout` seconds have passed since
+    /// `last_action_at` or the last unpause, whichever is later. The pot is
+    /// split evenly among the players still alive, or, if the last collapse
+    /// eliminated everyone, among those it eliminated; any remainder is paid
+    /// one unit each to the earliest of them in roster order.
+    /// Remaining accounts: a destination token account for each of the
+    /// first recipients, in roster order, owned by that player's wallet;
+    /// `pay_out` pays any that don't fit in the transaction.
+    pub fn expire_game<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExpireGame<'info>>,
+        game_id: [u8; 16],
+    ) -> Result<()> {
+        // Authorities can't act while paused, so time spent paused, and the
+        // timeout after it, doesn't count against them
+        let config = &ctx.accounts.config;
+        require!(!config.paused, IgniteError::ProgramPaused);
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        game.transition(GameStatus::Expired)?;
+        require!(!game.seat_refunds_pending(), IgniteError::SeatRefundsPending);
 
-impl PlayerState {
-    pub const SIZE: usize = 35;
-}
+        let now = Clock::get()?.unix_timestamp;
+        let deadline = game
+            .last_action_at
+            .max(config.unpaused_at)
+            .checked_add(game.inactivity_timeout)
+            .unwrap();
+        require!(now >= deadline, IgniteError::InactivityTimeoutNotReached);
 
-// ─── Contexts ─────────────────────────────────────────────────────────────────
+        game.payout = game.prize_pool;
 
-#[derive(Accounts)]
-#[instruction(game_id: [u8; 16])]
-pub struct InitializeGame<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = GameState::SIZE,
-        seeds = [b"game_state", &game_id],
-        bump
-    )]
-    pub game_state: Account<'info, GameState>,
+        emit!(GameExpired {
+            game_id,
+            caller: ctx.accounts.caller.key(),
+            survivors: game.payees().iter().map(|&i| game.players[i].pubkey).collect(),
+            prize_pool: game.payout,
+        });
+        pay_payees(
+            game,
+            0,
+            ctx.remaining_accounts,
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.bumps.escrow_vault,
+        )
+    }
 
-    /// CHECK: escrow vault is a token account managed separately
-    #[account(
-        seeds = [b"escrow", &game_id],
-        bump
-    )]
-    pub escrow_vault: UncheckedAccount<'info>,
+    /// Cancel a game that never started and refund every player's buy-in.
+    /// The authority may cancel at any time before the start; anyone else may
+    /// once `cancel_timeout` seconds have passed since `created_at`.
+    /// Remaining accounts: a destination token account for each of the
+    /// first players, in roster order, owned by that player's wallet;
+    /// `pay_out` refunds any that don't fit in the transaction.
+    pub fn cancel_game<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelGame<'info>>,
+        game_id: [u8; 16],
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        game.transition(GameStatus::Cancelled)?;
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+        if ctx.accounts.caller.key() != game.authority {
+            let now = Clock::get()?.unix_timestamp;
+            let deadline = game.created_at.checked_add(game.cancel_timeout).unwrap();
+            require!(now >= deadline, IgniteError::CancelTimeoutNotReached);
+        }
 
-    pub system_program: Program<'info, System>,
-}
+        // Every player paid the buy-in, so even shares are exact refunds
+        game.payout = game.prize_pool;
 
-#[derive(Accounts)]
-#[instruction(game_id: [u8; 16])]
-pub struct JoinGame<'info> {
-    #[account(
-        mut,
-        seeds = [b"game_state", &game_id],
-        bump
-    )]
-    pub game_state: Account<'info, GameState>,
+        emit!(GameCancelled {
+            game_id,
+            caller: ctx.accounts.caller.key(),
+            refunded: game.players().iter().map(|p| p.pubkey).collect(),
+            refund: game.buy_in,
+        });
+        pay_payees(
+            game,
+            0,
+            ctx.remaining_accounts,
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.bumps.escrow_vault,
+        )
+    }
 
-    #[account(
-        mut,
-        seeds = [b"escrow", &game_id],
-        bump
-    )]
-    pub escrow_vault: Account<'info, TokenAccount>,
+    /// Admin-only, while the program is paused: unwind a waiting or active
+    /// game whose server has gone away. Everything in the escrow vault is
+    /// split evenly across the roster (every player paid the same buy-in,
+    /// eliminated or not) apart from seat forfeiters, who were refunded at
+    /// the start; any remainder goes one unit each to the earliest players
+    /// in roster order.
+    /// Remaining accounts: a destination token account for each of the
+    /// first players, in roster order, owned by that player's wallet;
+    /// `pay_out` refunds any that don't fit in the transaction.
+    pub fn emergency_refund<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyRefund<'info>>,
+        game_id: [u8; 16],
+    ) -> Result<()> {
+        require!(ctx.accounts.config.paused, IgniteError::ProgramNotPaused);
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        game.transition(GameStatus::Refunded)?;
+        require!(!game.seat_refunds_pending(), IgniteError::SeatRefundsPending);
 
-    #[account(mut)]
-    pub player_token_account: Account<'info, TokenAccount>,
+        // Sweep the vault itself so stray deposits don't block close_game
+        game.prize_pool = ctx.accounts.escrow_vault.amount;
+        game.payout = game.prize_pool;
 
-    pub player: Signer<'info>,
+        emit!(GameRefunded {
+            game_id,
+            refunded: game.payees().iter().map(|&i| game.players[i].pubkey).collect(),
+            total: game.payout,
+        });
+        pay_payees(
+            game,
+            0,
+            ctx.remaining_accounts,
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.bumps.escrow_vault,
+        )
+    }
 
-    pub token_program: Program<'info, Token>,
-}
+    /// Pay the payees a finished game's settling instruction (cancel, draw,
+    /// expiry or emergency refund), or an active game's `start_game` seat
+    /// refunds, had no room for, starting at payee `start`, counted from 0
+    /// over every payee in roster order. Anyone may call this, even while
+    /// paused, and each payee is paid only once.
+    /// Remaining accounts: a destination token account for each payee from
+    /// `start`, in roster order, owned by that player's wallet.
+    pub fn pay_out<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PayOut<'info>>,
+        _game_id: [u8; 16],
+        start: u8,
+    ) -> Result<()> {
+        let game = &mut *ctx.accounts.game_state.load_mut()?;
+        let status = game.status();
+        require!(
+            status.is_finished() || status == GameStatus::Active,
+            IgniteError::GameNotFinished
+        );
+        pay_payees(
+            game,
+            start as usize,
+            ctx.remaining_accounts,
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.bumps.escrow_vault,
+        )
+    }
+
+    /// Authority-only: close a finished game's escrow vault and GameState,
+    /// returning their rent to `rent_receiver`. The pot must be paid out.
+    pub fn close_game(ctx: Context<CloseGame>, game_id: [u8; 16]) -> Result<()> {
+        let game = ctx.accounts.game_state.load()?;
+        require!(game.status().is_finished(), IgniteError::GameNotFinished);
+        require!(
+            game.prize_pool == 0 && ctx.accounts.escrow_vault.amount == 0,
+            IgniteError::EscrowNotEmpty
+        );
+
+        close_escrow(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_vault,
+            ctx.accounts.rent_receiver.to_account_info(),
+            &game_id,
+            ctx.bumps.escrow_vault,
+        )?;
+
+        emit!(GameClosed {
+            game_id,
+            rent_receiver: game.rent_receiver,
+        });
+        Ok(())
+    }
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// Move `amount` out of a game's escrow vault, signing with the vault PDA.
+fn transfer_from_escrow<'info>(
+    token_program: &Program<'info, Token>,
+    escrow_vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    game_id: &[u8; 16],
+    bump: u8,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[b"escrow".as_ref(), game_id.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        Transfer {
+            from: escrow_vault.to_account_info(),
+            to,
+            authority: escrow_vault.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)
+}
+
+/// The tiles `CollapsePattern::Random` collapses in `round` (1-based), in
+/// draw order, on a `width`×`height` board where `taken` has a bit set for
+/// every tile that is already lava, cracking or blocked.
+///
+/// Draw `i` (from 0) hashes `sha256(seed || round || i as u32 LE)`, reads
+/// the first 8 bytes as a little-endian u64 `r`, and takes the
+/// `(r % n)`th of the `n` still-safe tiles in row-major order (y, then x),
+/// excluding tiles already drawn this round. Draws stop after `count`
+/// tiles or when no safe tile is left.
+pub fn collapse_schedule(
+    seed: &[u8; 32],
+    round: u8,
+    taken: &[u64; GRID_WORDS],
+    width: u8,
+    height: u8,
+    count: u8,
+) -> Vec<(u8, u8)> {
+    // Treat tiles off the board as taken so only clear bits are candidates
+    let width = width as usize;
+    let board = width * height as usize;
+    let mut taken = *taken;
+    for (w, word) in taken.iter_mut().enumerate() {
+        let off_board = (w * 64 + 64).saturating_sub(board).min(64);
+        if off_board > 0 {
+            *word |= u64::MAX << (64 - off_board);
+        }
+    }
+    let mut safe: u32 = taken.iter().map(|w| w.count_zeros()).sum();
+
+    let mut tiles = Vec::with_capacity(count as usize);
+    for i in 0..count as u32 {
+        if safe == 0 {
+            break;
+        }
+        let digest = hashv(&[seed, &[round], &i.to_le_bytes()]).to_bytes();
+        let r = u64::from_le_bytes(digest[..8].try_into().unwrap());
+        let mut nth = (r % safe as u64) as u32;
+        for (w, word) in taken.iter_mut().enumerate() {
+            let free = word.count_zeros();
+            if nth >= free {
+                nth -= free;
+                continue;
+            }
+            let bit = (0..64).filter(|b| *word & (1 << b) == 0).nth(nth as usize).unwrap();
+            *word |= 1 << bit;
+            let idx = w * 64 + bit;
+            tiles.push(((idx % width) as u8, (idx / width) as u8));
+            break;
+        }
+        safe -= 1;
+    }
+    tiles
+}
+
+/// The commitment `commit_move` takes for a sealed move:
+/// `sha256(direction as u8 || salt)`. Pick a fresh random salt each round.
+pub fn move_commitment(direction: Direction, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[&[direction as u8], salt]).to_bytes()
+}
+
+/// Pay payees `start..` of a game their even share of `payout`,
+/// one per destination account, marking each paid and drawing down
+/// `prize_pool`. Any remainder goes one unit each to the earliest payees.
+fn pay_payees<'info>(
+    game: &mut GameState,
+    start: usize,
+    accounts: &'info [AccountInfo<'info>],
+    token_program: &Program<'info, Token>,
+    escrow_vault: &Account<'info, TokenAccount>,
+    bump: u8,
+) -> Result<()> {
+    let payees = game.payees();
+    let end = start
+        .checked_add(accounts.len())
+        .filter(|&end| end <= payees.len())
+        .ok_or(IgniteError::RefundAccountsMismatch)?;
+
+    let mut paid = Vec::with_capacity(accounts.len());
+    for (k, info) in (start..end).zip(accounts) {
+        let player = game.players[payees[k]];
+        require!(!player.is_paid_out(), IgniteError::AlreadyPaidOut);
+        let dest: Account<TokenAccount> = Account::try_from(info)?;
+        require!(dest.mint == game.mint, IgniteError::InvalidMint);
+        require_keys_eq!(dest.owner, player.owner, IgniteError::InvalidRefundAccount);
+
+        let amount = even_share(game.payout, payees.len(), k);
+        transfer_from_escrow(
+            token_program,
+            escrow_vault,
+            info.clone(),
+            &game.game_id,
+            bump,
+            amount,
+        )?;
+        game.players[payees[k]].paid_out = 1;
+        game.prize_pool = game.prize_pool.checked_sub(amount).unwrap();
+        paid.push(player.pubkey);
+    }
+
+    if !paid.is_empty() {
+        emit!(PlayersPaid {
+            game_id: game.game_id,
+            players: paid,
+            prize_pool: game.prize_pool,
+        });
+    }
+    Ok(())
+}
+
+/// The `i`th of `count` even shares of `total`, with the remainder paid
+/// one unit each to the first shares.
+fn even_share(total: u64, count: usize, i: usize) -> u64 {
+    let count = count as u64;
+    let share = total / count;
+    if (i as u64) < total % count {
+        share + 1
+    } else {
+        share
+    }
+}
+
+/// Close a game's (empty) escrow vault, sending its rent to `destination`.
+fn close_escrow<'info>(
+    token_program: &Program<'info, Token>,
+    escrow_vault: &Account<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    game_id: &[u8; 16],
+    bump: u8,
+) -> Result<()> {
+    let seeds = &[b"escrow".as_ref(), game_id.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    let close_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow_vault.to_account_info(),
+            destination,
+            authority: escrow_vault.to_account_info(),
+        },
+        signer,
+    );
+    token::close_account(close_ctx)
+}
+
+/// Declares `$name`, the Pod form of the Borsh enum `$ty` kept in zero-copy
+/// accounts: its Borsh bytes, zero-padded to `$len`. The IDL describes the
+/// field as `$ty` itself, so clients decode the enum rather than a number.
+macro_rules! stored_enum {
+    ($name:ident, $ty:ident, $len:expr) => {
+        #[derive(Clone, Copy, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+        #[repr(transparent)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            pub fn get(self) -> $ty {
+                $ty::deserialize(&mut &self.0[..]).unwrap()
+            }
+        }
+
+        impl From<$ty> for $name {
+            fn from(value: $ty) -> Self {
+                let mut bytes = [0; $len];
+                value.serialize(&mut &mut bytes[..]).unwrap();
+                Self(bytes)
+            }
+        }
+
+        #[cfg(feature = "idl-build")]
+        impl anchor_lang::idl::build::IdlBuild for $name {
+            fn create_type() -> Option<anchor_lang::idl::types::IdlTypeDef> {
+                $ty::create_type()
+            }
+
+            fn insert_types(
+                types: &mut std::collections::BTreeMap<String, anchor_lang::idl::types::IdlTypeDef>,
+            ) {
+                $ty::insert_types(types)
+            }
+
+            fn get_full_path() -> String {
+                $ty::get_full_path()
+            }
+        }
+    };
+}
+
+// ─── Account Structs ──────────────────────────────────────────────────────────
+
+/// Program-wide settings, a singleton at `[b"config"]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    pub fee_bps: u16,     // rake on winner payouts, in basis points
+    pub treasury: Pubkey, // wallet whose token accounts receive the rake
+    #[max_len(MAX_AUTHORITIES)]
+    pub authorities: Vec<Pubkey>, // keys allowed to create and run games
+    #[max_len(MAX_MINTS)]
+    pub allowed_mints: Vec<Pubkey>,
+    pub min_buy_in: u64,
+    pub max_buy_in: u64,
+    pub paused: bool, // blocks new games, joins and gameplay
+    pub unpaused_at: i64, // last set_paused(false); restarts inactivity clocks
+}
+
+impl Config {
+    pub const SIZE: usize = 8 + Config::INIT_SPACE;
+
+    /// Validate and store `params`, emitting `ConfigUpdated`.
+    fn apply(&mut self, params: ConfigParams) -> Result<()> {
+        require!(params.fee_bps <= MAX_FEE_BPS, IgniteError::InvalidFee);
+        require!(
+            params.min_buy_in <= params.max_buy_in,
+            IgniteError::InvalidBuyInRange
+        );
+        require!(
+            params.authorities.len() <= MAX_AUTHORITIES
+                && params.allowed_mints.len() <= MAX_MINTS,
+            IgniteError::ConfigListTooLong
+        );
+        self.fee_bps = params.fee_bps;
+        self.treasury = params.treasury;
+        self.authorities = params.authorities;
+        self.allowed_mints = params.allowed_mints;
+        self.min_buy_in = params.min_buy_in;
+        self.max_buy_in = params.max_buy_in;
+
+        emit!(ConfigUpdated {
+            admin: self.admin,
+            fee_bps: self.fee_bps,
+            treasury: self.treasury,
+            authorities: self.authorities.clone(),
+            allowed_mints: self.allowed_mints.clone(),
+            min_buy_in: self.min_buy_in,
+            max_buy_in: self.max_buy_in,
+        });
+        Ok(())
+    }
+}
+
+/// Admin-chosen Config settings, for `initialize_config`/`update_config`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub authorities: Vec<Pubkey>,
+    pub allowed_mints: Vec<Pubkey>,
+    pub min_buy_in: u64,
+    pub max_buy_in: u64,
+}
+
+/// One game's state, read in place (zero-copy) so large boards and rosters
+/// don't have to be deserialized on every instruction. Fields are ordered
+/// by alignment; enums are stored as their Borsh bytes, options as an
+/// all-zero "none", and the roster as the first `player_count` entries.
+#[account(zero_copy)]
+pub struct GameState {
+    pub game_id: [u8; 16],
+    pub authority: Pubkey,
+    pub mint: Pubkey,            // SPL mint for buy-ins and payouts
+    pub buy_in: u64,
+    pub prize_pool: u64,
+    pub created_at: i64,
+    pub cancel_timeout: i64,     // seconds after created_at anyone may cancel
+    pub lobby_timeout: i64,      // seconds after created_at anyone may start
+    pub inactivity_timeout: i64, // seconds after last_action_at anyone may expire
+    pub last_action_at: i64,     // last lock_lobby, start_game or authority game step
+    pub fee_paid: u64,           // rake taken by declare_winner
+    pub payout: u64,             // pot split among payees() once finished
+    pub lava: [u64; GRID_WORDS], // bit y * width + x set once that tile is lava
+    pub cracking: [u64; GRID_WORDS], // lava after the next collapse round
+    pub blocked: [u64; GRID_WORDS], // walls and holes from the arena mask
+    pub winner: Pubkey,
+    pub seed_commitment: [u8; 32], // sha256(seed); fixes the collapse schedule
+    pub seed: [u8; 32],          // collapse RNG seed, mixed by reveal_seed
+    pub rent_receiver: Pubkey,   // gets the rent back on close_game
+    pub players: [PlayerState; MAX_PLAYERS],
+    pub fee_bps: u16,            // rake snapshotted from Config at creation
+    pub status: StoredGameStatus,
+    pub width: u8,
+    pub height: u8,
+    pub player_count: u8,
+    pub collapse_round: u8,
+    pub require_cosign: u8,      // moves must be co-signed by authority
+    pub min_players: u8,         // players needed before start_game
+    pub max_players: u8,         // lobby capacity (≤ MAX_PLAYERS)
+    pub forfeit_rule: StoredForfeitRule, // applies to unrevealed entropy at start_game
+    pub sealed_moves: u8,        // moves go through commit_move / reveal_move
+    pub move_phase: StoredMovePhase, // always Commit unless sealed_moves
+    // Last, as the only variable-width Borsh enum: decoders reading it by
+    // variant only misplace the padding after it
+    pub collapse_pattern: StoredCollapsePattern, // how each round's tiles are chosen
+    pub _padding: [u8; 1],
+}
+
+impl GameState {
+    pub fn status(&self) -> GameStatus {
+        self.status.get()
+    }
+
+    pub fn collapse_pattern(&self) -> CollapsePattern {
+        self.collapse_pattern.get()
+    }
+
+    pub fn forfeit_rule(&self) -> ForfeitRule {
+        self.forfeit_rule.get()
+    }
+
+    pub fn requires_cosign(&self) -> bool {
+        self.require_cosign != 0
+    }
+
+    pub fn has_sealed_moves(&self) -> bool {
+        self.sealed_moves != 0
+    }
+
+    pub fn move_phase(&self) -> MovePhase {
+        self.move_phase.get()
+    }
+
+    /// Sealed-move games only collapse once the round's moves are resolved.
+    pub fn ready_to_collapse(&self) -> bool {
+        !self.has_sealed_moves() || self.move_phase() == MovePhase::Resolved
+    }
+
+    pub fn winner(&self) -> Option<Pubkey> {
+        Some(self.winner).filter(|w| *w != Pubkey::default())
+    }
+
+    pub fn seed_commitment(&self) -> Option<[u8; 32]> {
+        Some(self.seed_commitment).filter(|c| *c != [0; 32])
+    }
+
+    pub fn seed(&self) -> Option<[u8; 32]> {
+        Some(self.seed).filter(|s| *s != [0; 32])
+    }
+
+    /// Everyone who has joined, in roster order.
+    pub fn players(&self) -> &[PlayerState] {
+        &self.players[..self.player_count as usize]
+    }
+
+    pub fn players_mut(&mut self) -> &mut [PlayerState] {
+        &mut self.players[..self.player_count as usize]
+    }
+
+    /// Index of tile `(x, y)` in the bitsets, or `None` if it is off the board.
+    pub fn tile(&self, x: u8, y: u8) -> Option<usize> {
+        (x < self.width && y < self.height)
+            .then(|| y as usize * self.width as usize + x as usize)
+    }
+
+    pub fn is_lava(&self, tile: usize) -> bool {
+        self.lava[tile / 64] & (1 << (tile % 64)) != 0
+    }
+
+    /// Collapsing: still standable, but lava after the next round.
+    pub fn is_cracking(&self, tile: usize) -> bool {
+        self.cracking[tile / 64] & (1 << (tile % 64)) != 0
+    }
+
+    /// Walls and holes: never playable, never collapsed.
+    pub fn is_blocked(&self, tile: usize) -> bool {
+        self.blocked[tile / 64] & (1 << (tile % 64)) != 0
+    }
+
+    /// Playable and not yet collapsing.
+    pub fn is_safe(&self, tile: usize) -> bool {
+        !self.is_lava(tile) && !self.is_cracking(tile) && !self.is_blocked(tile)
+    }
+
+    /// Block the tiles set in `mask`, where tile `i` is bit `i % 8` of byte
+    /// `i / 8`. The mask must cover exactly this board.
+    fn block_tiles(&mut self, mask: &[u8]) -> Result<()> {
+        let tiles = self.width as usize * self.height as usize;
+        require!(
+            mask.len() == tiles.div_ceil(8),
+            IgniteError::InvalidArenaMask
+        );
+        for (word, chunk) in self.blocked.iter_mut().zip(mask.chunks(8)) {
+            let mut bytes = [0u8; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_le_bytes(bytes);
+        }
+        require!(
+            (tiles..mask.len() * 8).all(|i| !self.is_blocked(i)),
+            IgniteError::InvalidArenaMask
+        );
+        Ok(())
+    }
+
+    /// Move the game to `next`, rejecting any edge not in the state machine.
+    /// Every instruction that changes `status` goes through here.
+    pub fn transition(&mut self, next: GameStatus) -> Result<()> {
+        require!(
+            self.status().can_transition_to(next),
+            IgniteError::InvalidStatusTransition
+        );
+        self.status = next.into();
+        Ok(())
+    }
+
+    /// Turn last round's cracking tiles to lava, start the safe tiles among
+    /// `tiles` cracking, and eliminate the living players left on lava in
+    /// `round`, which becomes the current round, and reopen moves, dropping
+    /// any sealed ones left unrevealed. Returns who fell.
+    pub fn collapse(&mut self, tiles: &[(u8, u8)], round: u8) -> Vec<Pubkey> {
+        for (lava, cracking) in self.lava.iter_mut().zip(self.cracking.iter_mut()) {
+            *lava |= *cracking;
+            *cracking = 0;
+        }
+        for &(tx, ty) in tiles {
+            if let Some(idx) = self.tile(tx, ty).filter(|&t| self.is_safe(t)) {
+                self.cracking[idx / 64] |= 1 << (idx % 64);
+            }
+        }
+
+        let mut eliminated = vec![];
+        for i in 0..self.player_count as usize {
+            let p = &mut self.players[i];
+            p.moved = 0;
+            p.move_commitment = [0; 32];
+            p.move_revealed = 0;
+            let p = self.players[i];
+            if p.is_alive() && self.tile(p.x, p.y).is_some_and(|t| self.is_lava(t)) {
+                self.players[i].alive = 0;
+                self.players[i].eliminated_in = round;
+                eliminated.push(p.pubkey);
+            }
+        }
+
+        self.collapse_round = round;
+        self.move_phase = MovePhase::Commit.into();
+        eliminated
+    }
+
+    /// What `ForfeitRule::Seat` hands back: half the buy-in, rounded down.
+    /// The rest stays in the pot, so withholding entropy is never free.
+    pub fn seat_refund(&self) -> u64 {
+        self.buy_in / 2
+    }
+
+    pub fn alive_count(&self) -> usize {
+        self.players().iter().filter(|p| p.is_alive()).count()
+    }
+
+    /// Roster indices of the players owed a share of `payout`: seat
+    /// forfeiters while active, everyone else when cancelled or refunded,
+    /// and after a draw or expiry the survivors or, if there are none, the
+    /// final round's players.
+    pub fn payees(&self) -> Vec<usize> {
+        let seat = self.forfeit_rule() == ForfeitRule::Seat;
+        let any_alive = self.alive_count() > 0;
+        let final_round = self.final_round();
+        (0..self.player_count as usize)
+            .filter(|&i| {
+                let p = &self.players[i];
+                let refunded_seat = seat && p.is_forfeited();
+                let finalist = !any_alive && p.eliminated_in == final_round;
+                match self.status() {
+                    GameStatus::Active => refunded_seat,
+                    GameStatus::Cancelled | GameStatus::Refunded => !refunded_seat,
+                    GameStatus::Draw | GameStatus::Expired => p.is_alive() || finalist,
+                    _ => false,
+                }
+            })
+            .collect()
+    }
+
+    /// A drawn game's payees: the players the last collapse eliminated,
+    /// once nobody is left alive.
+    pub fn finalists(&self) -> Result<Vec<usize>> {
+        require!(self.alive_count() == 0, IgniteError::GameNotDrawn);
+        let finalists = self.payees();
+        require!(!finalists.is_empty(), IgniteError::NoFinalists);
+        Ok(finalists)
+    }
+
+    /// Split `prize_pool` into the treasury's rake at `fee_bps` and the
+    /// winner's payout.
+    pub fn split_pot(&self) -> Result<(u64, u64)> {
+        let fee = (self.prize_pool as u128)
+            .checked_mul(self.fee_bps as u128)
+            .and_then(|v| v.checked_div(10_000))
+            .and_then(|v| u64::try_from(v).ok())
+            .ok_or(IgniteError::MathOverflow)?;
+        let payout = self
+            .prize_pool
+            .checked_sub(fee)
+            .ok_or(IgniteError::MathOverflow)?;
+        Ok((fee, payout))
+    }
+
+    /// Whether a `ForfeitRule::Seat` forfeiter is still owed their refund.
+    /// The game can't settle until `pay_out` has paid them all.
+    pub fn seat_refunds_pending(&self) -> bool {
+        self.forfeit_rule() == ForfeitRule::Seat
+            && self.players().iter().any(|p| p.is_forfeited() && !p.is_paid_out())
+    }
+
+    /// The last round that eliminated anyone. Once nobody is left, its
+    /// players are the finalists who share the pot.
+    pub fn final_round(&self) -> u8 {
+        let fallen = self.players().iter().filter(|p| !p.is_alive());
+        fallen.map(|p| p.eliminated_in).max().unwrap_or(0)
+    }
+
+    /// Apply every revealed sealed move at once and clear the round's
+    /// commitments. A move is dropped if it leaves the board or enters a
+    /// wall or lava, and bounces if it ends on the same tile as another
+    /// living player or swaps places with one. Bounced players stay put
+    /// and can block others in turn; each bounce rechecks only the movers
+    /// heading for its tile, so a chain of bounces costs one walk, not one
+    /// rescan of the roster per link.
+    /// Returns the players who bounced.
+    pub fn resolve_moves(&mut self) -> Vec<Pubkey> {
+        let n = self.player_count as usize;
+        let from: Vec<(u8, u8)> = self.players().iter().map(|p| (p.x, p.y)).collect();
+        let mut to = from.clone();
+        let mut moving = vec![false; n];
+        let open = |&(x, y): &(u8, u8)| {
+            self.tile(x, y).is_some_and(|t| !self.is_blocked(t) && !self.is_lava(t))
+        };
+        for (i, p) in self.players().iter().enumerate() {
+            let target = p
+                .revealed_move()
+                .filter(|_| p.is_alive())
+                .and_then(|d| d.step(p.x, p.y))
+                .filter(open);
+            if let Some(target) = target {
+                to[i] = target;
+                moving[i] = true;
+            }
+        }
+
+        // Who stands on each tile, and which movers are heading for it
+        let mut standing = BTreeMap::new();
+        let mut wanted: BTreeMap<(u8, u8), Vec<usize>> = BTreeMap::new();
+        for i in 0..n {
+            if self.players[i].is_alive() {
+                standing.insert(from[i], i);
+            }
+            if moving[i] {
+                wanted.entry(to[i]).or_default().push(i);
+            }
+        }
+
+        // Each player bounces at most once, and only a bounce can block
+        // anyone new: those heading for the bounced player's tile.
+        let mut bounced = vec![false; n];
+        let mut queue: Vec<usize> = (0..n).filter(|&i| moving[i]).collect();
+        while let Some(i) = queue.pop() {
+            if !moving[i] {
+                continue;
+            }
+            let contested = wanted[&to[i]].len() > 1;
+            let blocked = standing.get(&to[i]).is_some_and(|&j| !moving[j] || to[j] == from[i]);
+            if contested || blocked {
+                moving[i] = false;
+                to[i] = from[i];
+                bounced[i] = true;
+                queue.extend(wanted.get(&from[i]).into_iter().flatten());
+            }
+        }
+        let bounced = (0..n).filter(|&i| bounced[i]).map(|i| self.players[i].pubkey).collect();
+
+        for (p, &(x, y)) in self.players_mut().iter_mut().zip(&to) {
+            (p.x, p.y) = (x, y);
+            p.move_commitment = [0; 32];
+            p.move_revealed = 0;
+        }
+        bounced
+    }
+
+    /// The tiles `collapse_pattern` starts cracking in `round` (1-based)
+    /// given the current board. Fixed patterns list their still-safe tiles
+    /// in row-major order (y, then x); `Random` uses `collapse_schedule`.
+    /// Only the tiles in the round's line, ring or half are visited, so
+    /// the cost scales with what collapses rather than the board size.
+    pub fn scheduled_collapse(&self, round: u8) -> Result<Vec<(u8, u8)>> {
+        let (width, height) = (self.width, self.height);
+        let r = round.saturating_sub(1);
+        let tiles: Vec<(u8, u8)> = match self.collapse_pattern() {
+            CollapsePattern::Manual => return err!(IgniteError::ManualCollapsePattern),
+            CollapsePattern::Random { count } => {
+                let seed = self.seed().ok_or(IgniteError::SeedNotRevealed)?;
+                let taken =
+                    std::array::from_fn(|i| self.lava[i] | self.cracking[i] | self.blocked[i]);
+                return Ok(collapse_schedule(&seed, round, &taken, width, height, count));
+            }
+            CollapsePattern::RingShrink => {
+                // Ring r spans [r, right] × [r, bottom]; its inner rows only
+                // touch the two edge columns
+                let right = width.saturating_sub(r + 1);
+                let bottom = height.saturating_sub(r + 1);
+                (r..=bottom)
+                    .flat_map(|y| {
+                        let edge = y == r || y == bottom;
+                        (r..=right)
+                            .filter(move |&x| edge || x == r || x == right)
+                            .map(move |x| (x, y))
+                    })
+                    .collect()
+            }
+            CollapsePattern::RowSweep if r < height => (0..width).map(|x| (x, r)).collect(),
+            CollapsePattern::ColumnSweep if r < width => (0..height).map(|y| (r, y)).collect(),
+            CollapsePattern::Checkerboard if r < 2 => (0..height)
+                .flat_map(|y| ((r + y) % 2..width).step_by(2).map(move |x| (x, y)))
+                .collect(),
+            CollapsePattern::RowSweep
+            | CollapsePattern::ColumnSweep
+            | CollapsePattern::Checkerboard => vec![],
+        };
+        Ok(tiles
+            .into_iter()
+            .filter(|&(x, y)| self.is_safe(self.tile(x, y).unwrap()))
+            .collect())
+    }
+
+    /// Append to the roster, refusing to outgrow the allocated space.
+    pub fn add_player(&mut self, player: PlayerState) -> Result<()> {
+        require!(
+            (self.player_count as usize) < MAX_PLAYERS,
+            IgniteError::RosterCapacityExceeded
+        );
+        self.players[self.player_count as usize] = player;
+        self.player_count += 1;
+        Ok(())
+    }
+
+    /// Remove roster entry `idx`, keeping the rest in order.
+    pub fn remove_player(&mut self, idx: usize) -> PlayerState {
+        let player = self.players[idx];
+        let count = self.player_count as usize;
+        self.players.copy_within(idx + 1..count, idx);
+        self.players[count - 1] = PlayerState::zeroed();
+        self.player_count -= 1;
+        player
+    }
+
+    // 8 (discriminator) + the in-place layout
+    pub const SIZE: usize = 8 + std::mem::size_of::<GameState>();
+}
+
+/// Per-game settings chosen by the authority at `initialize_game`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InitializeGameParams {
+    pub buy_in: u64,
+    pub width: u8,
+    pub height: u8,
+    /// Walls and holes: tile `y * width + x` is blocked if bit `i % 8` of
+    /// byte `i / 8` is set. `None` leaves the whole rectangle playable.
+    pub mask: Option<Vec<u8>>,
+    pub require_cosign: bool,
+    pub cancel_timeout: i64,
+    pub min_players: u8,
+    pub max_players: u8,
+    pub lobby_timeout: i64,
+    /// Seconds without an authority step (a collapse, `open_reveals`,
+    /// `resolve_moves` or `reveal_seed`) after which anyone may `expire_game`
+    pub inactivity_timeout: i64,
+    /// `sha256(seed)`; required by, and only allowed with, the random
+    /// collapse pattern
+    pub seed_commitment: Option<[u8; 32]>,
+    pub collapse_pattern: CollapsePattern,
+    /// What seeded-game players lose if they don't reveal their entropy
+    pub forfeit_rule: ForfeitRule,
+    /// Where rent goes on `close_game`; defaults to the authority
+    pub rent_receiver: Option<Pubkey>,
+    /// Moves are committed with `commit_move`, revealed with `reveal_move`
+    /// and applied together by `resolve_moves`; `submit_move` is refused
+    pub sealed_moves: bool,
+}
+
+/// How each collapse round's tiles are chosen, fixed at `initialize_game`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CollapsePattern {
+    /// The authority picks any tiles with `trigger_collapse`
+    Manual,
+    /// Round `r` takes ring `r - 1`, counting in from the edge
+    RingShrink,
+    /// Round `r` takes `count` tiles drawn by `collapse_schedule` from the
+    /// committed seed; needs a `seed_commitment`
+    Random { count: u8 },
+    /// Round `r` takes row `y = r - 1`
+    RowSweep,
+    /// Round `r` takes column `x = r - 1`
+    ColumnSweep,
+    /// Round 1 takes every tile with `x + y` even, round 2 the rest
+    Checkerboard,
+}
+
+stored_enum!(StoredCollapsePattern, CollapsePattern, 2);
+
+/// What a player in a seeded game forfeits by not revealing their entropy
+/// before `start_game`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ForfeitRule {
+    /// Starts eliminated; the buy-in stays in the pot
+    BuyIn,
+    /// Starts eliminated and is owed `GameState::seat_refund`, half the
+    /// buy-in, paid by `start_game` or `pay_out` before the game can
+    /// settle; the rest stays in the pot
+    Seat,
+}
+
+stored_enum!(StoredForfeitRule, ForfeitRule, 1);
+
+/// A one-tile step. `y` counts down the board, so `Up` decreases it.
+/// Serialized as a single byte, in declaration order.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// The coordinates one step this way from (x, y), unless they'd leave
+    /// the u8 range. The caller checks they're on the board.
+    pub fn step(self, x: u8, y: u8) -> Option<(u8, u8)> {
+        match self {
+            Direction::Up => Some((x, y.checked_sub(1)?)),
+            Direction::Down => Some((x, y.checked_add(1)?)),
+            Direction::Left => Some((x.checked_sub(1)?, y)),
+            Direction::Right => Some((x.checked_add(1)?, y)),
+        }
+    }
+}
+
+/// Where a sealed-move game is within the current collapse round.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MovePhase {
+    /// Players commit moves
+    Commit,
+    /// Players reveal; `resolve_moves` ends it
+    Reveal,
+    /// Moves applied; the collapse ends the round
+    Resolved,
+}
+
+stored_enum!(StoredMovePhase, MovePhase, 1);
+
+/// Lifecycle of a game. Serialized as a single byte, in declaration order.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameStatus {
+    /// Lobby open; players may join or leave
+    Waiting,
+    /// Match in progress
+    Active,
+    /// Exactly one survivor was paid the pot
+    Resolved,
+    /// Lobby closed before starting; buy-ins refunded
+    Cancelled,
+    /// Final collapse eliminated everyone; pot split among the last to fall
+    Draw,
+    /// Ended by inactivity timeout
+    Expired,
+    /// Unwound by the admin with `emergency_refund` while paused
+    Refunded,
+    /// Seeded lobby closed by `lock_lobby`; players reveal their entropy
+    Revealing,
+}
+
+stored_enum!(StoredGameStatus, GameStatus, 1);
+
+impl GameStatus {
+    /// True once the game has ended and the escrow holds nothing owed.
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            GameStatus::Resolved
+                | GameStatus::Cancelled
+                | GameStatus::Draw
+                | GameStatus::Expired
+                | GameStatus::Refunded
+        )
+    }
+
+    pub fn can_transition_to(self, next: GameStatus) -> bool {
+        use GameStatus::*;
+        matches!(
+            (self, next),
+            (Waiting, Active)
+                | (Waiting, Cancelled)
+                | (Active, Resolved)
+                | (Active, Draw)
+                | (Active, Expired)
+                | (Waiting, Refunded)
+                | (Active, Refunded)
+                | (Waiting, Revealing)
+                | (Revealing, Active)
+                | (Revealing, Cancelled)
+                | (Revealing, Refunded)
+        )
+    }
+}
+
+#[zero_copy]
+pub struct PlayerState {
+    pub pubkey: Pubkey,    // signs moves (wallet or session key)
+    pub owner: Pubkey,     // wallet that paid the buy-in
+    pub entropy_commitment: [u8; 32], // sha256(entropy), seeded games
+    pub entropy: [u8; 32], // revealed by reveal_entropy
+    pub x: u8,
+    pub y: u8,
+    pub alive: u8,
+    pub eliminated_in: u8, // collapse round that eliminated them
+    pub revealed: u8,      // entropy holds the revealed value
+    pub moved: u8,         // moved or committed a move since the last collapse
+    pub move_commitment: [u8; 32], // move_commitment(direction, salt); sealed games
+    pub direction: u8,     // Direction revealed by reveal_move
+    pub move_revealed: u8, // direction holds the revealed move
+    pub paid_out: u8,      // received their payout or refund
+    pub forfeited: u8,     // hadn't revealed their entropy at start_game
+}
+
+impl PlayerState {
+    pub fn is_alive(&self) -> bool {
+        self.alive != 0
+    }
+
+    pub fn entropy(&self) -> Option<[u8; 32]> {
+        (self.revealed != 0).then_some(self.entropy)
+    }
+
+    pub fn has_moved(&self) -> bool {
+        self.moved != 0
+    }
+
+    pub fn is_paid_out(&self) -> bool {
+        self.paid_out != 0
+    }
+
+    pub fn is_forfeited(&self) -> bool {
+        self.forfeited != 0
+    }
+
+    pub fn revealed_move(&self) -> Option<Direction> {
+        (self.move_revealed != 0).then(|| Direction::try_from_slice(&[self.direction]).unwrap())
+    }
+}
+
+// ─── Contexts ─────────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RotateAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        has_one = admin
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    pub new_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct AllocateGame<'info> {
+    /// CHECK: created or grown here; `initialize_game` checks it is fully
+    /// allocated and not yet initialized
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct InitializeGame<'info> {
+    /// Sized by `allocate_game`
+    #[account(
+        zero,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump,
+        constraint = game_state.as_ref().data_len() >= GameState::SIZE
+            @ IgniteError::GameNotAllocated
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow_vault
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct JoinGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump,
+        constraint = escrow_vault.mint == game_state.load()?.mint @ IgniteError::InvalidMint
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game_state.load()?.mint @ IgniteError::InvalidMint
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub player: Signer<'info>,
+
+    /// Optional delegated key that will sign moves instead of `player`
+    pub session_key: Option<Signer<'info>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct StartGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// Game authority, or anyone once the lobby timeout has elapsed
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct LockLobby<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// Game authority, or anyone once the lobby timeout has elapsed
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct RevealEntropy<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// The player's wallet or session key
+    pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct LeaveGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    /// Owner is checked against the leaving player's wallet in the handler
+    #[account(
+        mut,
+        constraint = player_token_account.mint == game_state.load()?.mint @ IgniteError::InvalidMint
+    )]
+    pub player_token_account: Account<'info, TokenAccount>,
+
+    pub player: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
 
 #[derive(Accounts)]
 #[instruction(game_id: [u8; 16])]
 pub struct SubmitMove<'info> {
     #[account(
         mut,
-        seeds = [b"game_state", &game_id],
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub player: Signer<'info>,
+
+    /// Authority co-signs to validate server-side move logic.
+    /// Required only when `game_state.require_cosign` is set.
+    #[account(
+        constraint = authority.key() == game_state.load()?.authority @ IgniteError::InvalidAuthority
+    )]
+    pub authority: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct RevealMove<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
         bump
     )]
-    pub game_state: Account<'info, GameState>,
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
 
     pub player: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct RevealSeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump,
+        has_one = authority
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
 
-    /// Authority co-signs to validate server-side move logic
     pub authority: Signer<'info>,
 }
 
@@ -306,11 +2184,14 @@ pub struct SubmitMove<'info> {
 pub struct TriggerCollapse<'info> {
     #[account(
         mut,
-        seeds = [b"game_state", &game_id],
+        seeds = [b"game_state", game_id.as_ref()],
         bump,
         has_one = authority
     )]
-    pub game_state: Account<'info, GameState>,
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
 
     pub authority: Signer<'info>,
 }
@@ -320,32 +2201,376 @@ pub struct TriggerCollapse<'info> {
 pub struct DeclareWinner<'info> {
     #[account(
         mut,
-        seeds = [b"game_state", &game_id],
+        seeds = [b"game_state", game_id.as_ref()],
+        bump,
+        has_one = authority
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump,
+        constraint = escrow_vault.mint == game_state.load()?.mint @ IgniteError::InvalidMint
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    /// Owner is checked against the surviving player in the handler
+    #[account(
+        mut,
+        constraint = winner_token_account.mint == game_state.load()?.mint @ IgniteError::InvalidMint
+    )]
+    pub winner_token_account: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == game_state.load()?.mint
+            @ IgniteError::InvalidMint,
+        constraint = treasury_token_account.owner == config.treasury @ IgniteError::InvalidTreasuryAccount
+    )]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct DeclareDraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
         bump,
         has_one = authority
     )]
-    pub game_state: Account<'info, GameState>,
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct ExpireGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// Anyone, once the inactivity timeout has elapsed
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct CancelGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    /// Game authority, or anyone once the cancel timeout has elapsed
+    pub caller: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct EmergencyRefund<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    #[account(seeds = [b"config"], bump, has_one = admin)]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct PayOut<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow", game_id.as_ref()],
+        bump
+    )]
+    pub escrow_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: [u8; 16])]
+pub struct CloseGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game_state", game_id.as_ref()],
+        bump,
+        has_one = authority,
+        has_one = rent_receiver,
+        close = rent_receiver
+    )]
+    pub game_state: AccountLoader<'info, GameState>,
 
     #[account(
         mut,
-        seeds = [b"escrow", &game_id],
+        seeds = [b"escrow", game_id.as_ref()],
         bump
     )]
     pub escrow_vault: Account<'info, TokenAccount>,
 
+    /// CHECK: matched against `game_state.rent_receiver`; only receives lamports
     #[account(mut)]
-    pub winner_token_account: Account<'info, TokenAccount>,
+    pub rent_receiver: UncheckedAccount<'info>,
 
     pub authority: Signer<'info>,
 
     pub token_program: Program<'info, Token>,
 }
 
+// ─── Events ───────────────────────────────────────────────────────────────────
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub fee_bps: u16,
+    pub treasury: Pubkey,
+    pub authorities: Vec<Pubkey>,
+    pub allowed_mints: Vec<Pubkey>,
+    pub min_buy_in: u64,
+    pub max_buy_in: u64,
+}
+
+#[event]
+pub struct ProgramPaused {
+    pub paused: bool, // false when resumed
+}
+
+#[event]
+pub struct AdminRotated {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct GameCreated {
+    pub game_id: [u8; 16],
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub buy_in: u64,
+    pub width: u8,
+    pub height: u8,
+    pub mask: Option<Vec<u8>>,
+    pub min_players: u8,
+    pub max_players: u8,
+    pub seed_commitment: Option<[u8; 32]>,
+    pub collapse_pattern: CollapsePattern,
+    pub sealed_moves: bool,
+}
+
+#[event]
+pub struct PlayerJoined {
+    pub game_id: [u8; 16],
+    pub player: Pubkey,
+    pub owner: Pubkey,
+    pub x: u8,
+    pub y: u8,
+    pub prize_pool: u64,
+}
+
+#[event]
+pub struct PlayerLeft {
+    pub game_id: [u8; 16],
+    pub player: Pubkey,
+    pub refund: u64,
+}
+
+#[event]
+pub struct GameStarted {
+    pub game_id: [u8; 16],
+    pub player_count: u8,
+    pub forfeited: Vec<Pubkey>,
+}
+
+#[event]
+pub struct LobbyLocked {
+    pub game_id: [u8; 16],
+    pub player_count: u8,
+}
+
+#[event]
+pub struct EntropyRevealed {
+    pub game_id: [u8; 16],
+    pub player: Pubkey,
+    pub entropy: [u8; 32],
+}
+
+#[event]
+pub struct PlayerMoved {
+    pub game_id: [u8; 16],
+    pub player: Pubkey,
+    pub x: u8,
+    pub y: u8,
+    pub round: u8, // collapse round the move was made in
+}
+
+#[event]
+pub struct MoveCommitted {
+    pub game_id: [u8; 16],
+    pub player: Pubkey,
+    pub round: u8,
+}
+
+#[event]
+pub struct RevealsOpened {
+    pub game_id: [u8; 16],
+    pub round: u8,
+}
+
+#[event]
+pub struct MoveRevealed {
+    pub game_id: [u8; 16],
+    pub player: Pubkey,
+    pub direction: Direction,
+}
+
+#[event]
+pub struct MovesResolved {
+    pub game_id: [u8; 16],
+    pub round: u8,
+    pub bounced: Vec<Pubkey>, // collided and stayed put; each move is a PlayerMoved
+}
+
+#[event]
+pub struct SeedRevealed {
+    pub game_id: [u8; 16],
+    pub seed: [u8; 32],
+}
+
+#[event]
+pub struct TilesCollapsed {
+    pub game_id: [u8; 16],
+    pub round: u8,
+    pub tiles: Vec<(u8, u8)>, // now cracking; the previous round's turned to lava
+    pub eliminated: Vec<Pubkey>,
+}
+
+#[event]
+pub struct WinnerDeclared {
+    pub game_id: [u8; 16],
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub fee: u64,
+}
+
+#[event]
+pub struct GameDrawn {
+    pub game_id: [u8; 16],
+    pub round: u8,
+    pub finalists: Vec<Pubkey>,
+    pub prize_pool: u64,
+}
+
+#[event]
+pub struct GameClosed {
+    pub game_id: [u8; 16],
+    pub rent_receiver: Pubkey,
+}
+
+#[event]
+pub struct GameCancelled {
+    pub game_id: [u8; 16],
+    pub caller: Pubkey,
+    pub refunded: Vec<Pubkey>,
+    pub refund: u64,
+}
+
+#[event]
+pub struct GameExpired {
+    pub game_id: [u8; 16],
+    pub caller: Pubkey,
+    pub survivors: Vec<Pubkey>,
+    pub prize_pool: u64,
+}
+
+#[event]
+pub struct GameRefunded {
+    pub game_id: [u8; 16],
+    pub refunded: Vec<Pubkey>,
+    pub total: u64,
+}
+
+#[event]
+pub struct PlayersPaid {
+    pub game_id: [u8; 16],
+    pub players: Vec<Pubkey>,
+    pub prize_pool: u64, // still owed to unpaid payees
+}
+
 // ─── Errors ───────────────────────────────────────────────────────────────────
 
 #[error_code]
 pub enum IgniteError {
-    #[msg("Invalid grid size — maximum is 10.")]
+    #[msg("Invalid grid size — width and height must be 1 to 64.")]
     InvalidGridSize,
     #[msg("Game is not in waiting status.")]
     GameNotJoinable,
@@ -367,4 +2592,116 @@ pub enum IgniteError {
     InvalidMove,
     #[msg("Game has not resolved to exactly one survivor yet.")]
     GameNotResolved,
+    #[msg("Token account mint does not match the game's mint.")]
+    InvalidMint,
+    #[msg("Winner token account is not owned by the winning player.")]
+    InvalidWinnerAccount,
+    #[msg("Player has already joined this game.")]
+    AlreadyJoined,
+    #[msg("This game requires the authority to co-sign moves.")]
+    CosignRequired,
+    #[msg("Signer is not the game authority.")]
+    InvalidAuthority,
+    #[msg("Timeouts must be positive.")]
+    InvalidTimeout,
+    #[msg("Only the authority can cancel before the timeout elapses.")]
+    CancelTimeoutNotReached,
+    #[msg("Expected at most one token account per remaining payee, in roster order.")]
+    RefundAccountsMismatch,
+    #[msg("Refund token account is not owned by the player.")]
+    InvalidRefundAccount,
+    #[msg("Game still has surviving players.")]
+    GameNotDrawn,
+    #[msg("Game cannot move from its current status to the requested one.")]
+    InvalidStatusTransition,
+    #[msg("Player limits must satisfy 2 ≤ min_players ≤ max_players ≤ 64.")]
+    InvalidPlayerLimits,
+    #[msg("Not enough players have joined to start the game.")]
+    NotEnoughPlayers,
+    #[msg("Only the authority can start the game before the lobby timeout elapses.")]
+    LobbyTimeoutNotReached,
+    #[msg("Game has not finished yet.")]
+    GameNotFinished,
+    #[msg("Escrow still holds funds.")]
+    EscrowNotEmpty,
+    #[msg("Player roster is at account capacity.")]
+    RosterCapacityExceeded,
+    #[msg("Fee must be at most 10000 basis points.")]
+    InvalidFee,
+    #[msg("Treasury token account is not owned by the configured treasury.")]
+    InvalidTreasuryAccount,
+    #[msg("Arithmetic overflow.")]
+    MathOverflow,
+    #[msg("The program is paused.")]
+    ProgramPaused,
+    #[msg("Signer is not an approved game authority.")]
+    UnapprovedAuthority,
+    #[msg("Mint is not allowed for games.")]
+    MintNotAllowed,
+    #[msg("Buy-in is outside the configured range.")]
+    BuyInOutOfRange,
+    #[msg("min_buy_in must not exceed max_buy_in.")]
+    InvalidBuyInRange,
+    #[msg("Too many approved authorities or allowed mints.")]
+    ConfigListTooLong,
+    #[msg("Emergency refunds require the program to be paused.")]
+    ProgramNotPaused,
+    #[msg("The game has not been inactive long enough to expire.")]
+    InactivityTimeoutNotReached,
+    #[msg("The random collapse pattern must take at least one tile per round.")]
+    InvalidCollapseCount,
+    #[msg("This game has no seed commitment.")]
+    NoSeedCommitted,
+    #[msg("The seed has already been revealed.")]
+    SeedAlreadyRevealed,
+    #[msg("Seed does not match the commitment.")]
+    SeedMismatch,
+    #[msg("The seed must be revealed before collapsing.")]
+    SeedNotRevealed,
+    #[msg("Tiles do not match this round's collapse schedule.")]
+    CollapseScheduleMismatch,
+    #[msg("Seeded games require an entropy commitment to join.")]
+    EntropyCommitmentRequired,
+    #[msg("Entropy has already been revealed.")]
+    EntropyAlreadyRevealed,
+    #[msg("Entropy does not match the commitment.")]
+    EntropyMismatch,
+    #[msg("A seed commitment is required by, and only allowed with, the random pattern.")]
+    InvalidCollapsePattern,
+    #[msg("This game has no collapse pattern; use trigger_collapse.")]
+    ManualCollapsePattern,
+    #[msg("The game account is already at full size.")]
+    GameAlreadyAllocated,
+    #[msg("The game account must be sized with allocate_game first.")]
+    GameNotAllocated,
+    #[msg("Arena mask must have one bit per tile and nothing past the last one.")]
+    InvalidArenaMask,
+    #[msg("That tile is a wall or hole.")]
+    TileNotPlayable,
+    #[msg("Player has already moved this round.")]
+    AlreadyMoved,
+    #[msg("Moves in this game are sealed; use commit_move.")]
+    MovesSealed,
+    #[msg("This game doesn't use sealed moves.")]
+    MovesNotSealed,
+    #[msg("Not allowed in the current move phase.")]
+    WrongMovePhase,
+    #[msg("No move committed this round.")]
+    NoMoveCommitted,
+    #[msg("Move already revealed.")]
+    MoveAlreadyRevealed,
+    #[msg("Revealed move doesn't match the commitment.")]
+    MoveMismatch,
+    #[msg("Fewer than two players are alive; declare the result instead.")]
+    GameAlreadyDecided,
+    #[msg("No player was eliminated in the final round.")]
+    NoFinalists,
+    #[msg("Player has already been paid out.")]
+    AlreadyPaidOut,
+    #[msg("Seeded games must be locked with lock_lobby before they start.")]
+    LobbyNotLocked,
+    #[msg("Entropy can only be revealed between lock_lobby and start_game.")]
+    EntropyRevealsClosed,
+    #[msg("Seat forfeiters must all be refunded with pay_out before the game settles.")]
+    SeatRefundsPending,
 }
@@ -0,0 +1,133 @@
+mod common;
+
+use common::*;
+use ignite::{CollapsePattern, GameState, IgniteError, InitializeGameParams};
+use solana_sdk::signer::Signer;
+
+/// A 6×3 arena mask with a wall at (1,0) and a hole at (4,2).
+fn arena_mask() -> Vec<u8> {
+    let mut mask = vec![0u8; 3];
+    for (x, y) in [(1, 0), (4, 2)] {
+        let i = y * 6 + x;
+        mask[i / 8] |= 1 << (i % 8);
+    }
+    mask
+}
+
+fn arena_params(collapse_pattern: CollapsePattern) -> InitializeGameParams {
+    InitializeGameParams {
+        width: 6,
+        height: 3,
+        mask: Some(arena_mask()),
+        collapse_pattern,
+        ..default_params()
+    }
+}
+
+fn lava(game: &GameState) -> Vec<(u8, u8)> {
+    (0..game.height)
+        .flat_map(|y| (0..game.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| game.is_lava(game.tile(x, y).unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn initialize_game_validates_arena() {
+    let mut h = Harness::new().await;
+    let (authority, mint) = (h.authority.pubkey(), h.mint);
+
+    let mut stray = arena_mask();
+    stray[2] |= 0x80; // tile 23, past the 18 on a 6×3 board
+    let cases = [
+        (
+            InitializeGameParams {
+                height: 0,
+                ..arena_params(CollapsePattern::Manual)
+            },
+            IgniteError::InvalidGridSize,
+        ),
+        (
+            InitializeGameParams {
+                mask: Some(vec![0u8; 4]),
+                ..arena_params(CollapsePattern::Manual)
+            },
+            IgniteError::InvalidArenaMask,
+        ),
+        (
+            InitializeGameParams {
+                mask: Some(stray),
+                ..arena_params(CollapsePattern::Manual)
+            },
+            IgniteError::InvalidArenaMask,
+        ),
+    ];
+    for (params, error) in cases {
+        let ixs = create_game_ixs(&authority, &mint, h.next_game_id(), params);
+        assert_ignite_error(h.send(&ixs, &[]).await, error);
+    }
+
+    let game_id = h.init_game(arena_params(CollapsePattern::Manual)).await;
+    let game = h.game(&game_id).await;
+    assert_eq!((game.width, game.height), (6, 3));
+    assert!(game.is_blocked(game.tile(1, 0).unwrap()));
+    assert!(game.is_blocked(game.tile(4, 2).unwrap()));
+    assert!(!game.is_blocked(game.tile(0, 0).unwrap()));
+}
+
+#[tokio::test]
+async fn join_and_move_reject_blocked_tiles() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(arena_params(CollapsePattern::Manual)).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+
+    assert_ignite_error(
+        h.join(game_id, &alice, 1, 0).await,
+        IgniteError::TileNotPlayable,
+    );
+    // x runs to the width, not the height
+    assert_ignite_error(
+        h.join(game_id, &alice, 2, 3).await,
+        IgniteError::OutOfBounds,
+    );
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 5, 2).await.unwrap();
+    h.start(game_id).await.unwrap();
+
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 0).await,
+        IgniteError::TileNotPlayable,
+    );
+    assert_ignite_error(
+        h.move_to(game_id, &bob, 4, 2).await,
+        IgniteError::TileNotPlayable,
+    );
+    h.move_to(game_id, &alice, 0, 1).await.unwrap();
+    h.move_to(game_id, &bob, 5, 1).await.unwrap();
+}
+
+#[tokio::test]
+async fn patterns_follow_the_rectangle_and_skip_blocked_tiles() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(arena_params(CollapsePattern::RingShrink)).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 1, 1).await.unwrap();
+    h.join(game_id, &bob, 4, 1).await.unwrap();
+    h.start(game_id).await.unwrap();
+
+    // The outer ring is the 14 edge tiles less the wall and the hole
+    let ring = h.game(&game_id).await.scheduled_collapse(1).unwrap();
+    assert_eq!(ring.len(), 12);
+    assert!(!ring.contains(&(1, 0)) && !ring.contains(&(4, 2)));
+    h.advance(game_id).await.unwrap();
+
+    // A 6×3 board has only one inner ring: the rest of the middle row
+    h.advance(game_id).await.unwrap();
+    assert_eq!(lava(&h.game(&game_id).await), ring);
+    h.advance(game_id).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert_eq!(lava(&game).len(), 16);
+    assert!(game.players().iter().all(|p| !p.is_alive()));
+    assert!(game.scheduled_collapse(4).unwrap().is_empty());
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use ignite::{CollapsePattern, GameState, IgniteError, InitializeGameParams};
+use solana_sdk::{hash::hash, signer::Signer};
+
+/// A started 5×5 game using `pattern`, with players at (0,0) and (2,2).
+async fn pattern_game(h: &mut Harness, pattern: CollapsePattern) -> ([u8; 16], Player, Player) {
+    let game_id = h
+        .init_game(InitializeGameParams {
+            collapse_pattern: pattern,
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 2, 2).await.unwrap();
+    h.start(game_id).await.unwrap();
+    (game_id, alice, bob)
+}
+
+/// The tiles in `state` (e.g. `GameState::is_lava`), in row-major order.
+fn board(game: &GameState, state: fn(&GameState, usize) -> bool) -> Vec<(u8, u8)> {
+    (0..5)
+        .flat_map(|y| (0..5).map(move |x| (x, y)))
+        .filter(|&(x, y)| state(game, game.tile(x, y).unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn initialize_game_validates_pattern_and_seed() {
+    let mut h = Harness::new().await;
+    let (authority, mint) = (h.authority.pubkey(), h.mint);
+    let commitment = Some(hash(&[7u8; 32]).to_bytes());
+    let cases = [
+        (None, CollapsePattern::Random { count: 2 }),
+        (commitment, CollapsePattern::RingShrink),
+        (commitment, CollapsePattern::Manual),
+    ];
+    for (seed_commitment, collapse_pattern) in cases {
+        let params = InitializeGameParams {
+            seed_commitment,
+            collapse_pattern,
+            ..default_params()
+        };
+        let ixs = create_game_ixs(&authority, &mint, h.next_game_id(), params);
+        assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::InvalidCollapsePattern);
+    }
+}
+
+#[tokio::test]
+async fn ring_shrink_closes_in_from_the_edge() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = pattern_game(&mut h, CollapsePattern::RingShrink).await;
+
+    // The authority can't substitute its own tiles
+    assert_ignite_error(
+        h.collapse(game_id, vec![(2, 2)]).await,
+        IgniteError::CollapseScheduleMismatch,
+    );
+
+    let expected = h.game(&game_id).await.scheduled_collapse(1).unwrap();
+    assert_eq!(expected.len(), 16);
+    h.advance(game_id).await.unwrap();
+
+    let game = h.game(&game_id).await;
+    assert_eq!(board(&game, GameState::is_cracking), expected);
+    assert_eq!(game.collapse_round, 1);
+    assert!(game.players().iter().all(|p| p.is_alive()));
+
+    // trigger_collapse is still accepted when it matches the pattern
+    let ring_two = game.scheduled_collapse(2).unwrap();
+    assert_eq!(ring_two.len(), 8);
+    h.collapse(game_id, ring_two.clone()).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert_eq!(board(&game, GameState::is_lava), expected);
+    assert_eq!(board(&game, GameState::is_cracking), ring_two);
+    assert!(!game.players[0].is_alive());
+    assert_eq!(game.players[0].pubkey, alice.key());
+    assert_eq!(game.players[0].eliminated_in, 2);
+    assert!(game.players[1].is_alive());
+
+    let authority = h.authority.pubkey();
+    let ix = declare_winner_ix(game_id, &authority, &bob.token, &h.treasury);
+    h.send(&[ix], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn row_and_column_sweeps() {
+    let mut h = Harness::new().await;
+    let (rows, _, _) = pattern_game(&mut h, CollapsePattern::RowSweep).await;
+    let (cols, _, _) = pattern_game(&mut h, CollapsePattern::ColumnSweep).await;
+
+    h.advance(rows).await.unwrap();
+    h.advance(rows).await.unwrap();
+    h.advance(cols).await.unwrap();
+
+    let game = h.game(&rows).await;
+    let row = |y| (0..5).map(move |x| (x, y)).collect::<Vec<_>>();
+    assert_eq!(board(&game, GameState::is_lava), row(0));
+    assert_eq!(board(&game, GameState::is_cracking), row(1));
+    let game = h.game(&cols).await;
+    assert!(board(&game, GameState::is_lava).is_empty());
+    let column = (0..5).map(|y| (0, y)).collect::<Vec<_>>();
+    assert_eq!(board(&game, GameState::is_cracking), column);
+}
+
+#[tokio::test]
+async fn checkerboard_takes_one_colour_per_round() {
+    let mut h = Harness::new().await;
+    let (game_id, _alice, _bob) = pattern_game(&mut h, CollapsePattern::Checkerboard).await;
+
+    h.advance(game_id).await.unwrap();
+    let game = h.game(&game_id).await;
+    let first = board(&game, GameState::is_cracking);
+    assert_eq!(first.len(), 13);
+    assert!(first.iter().all(|&(x, y)| (x + y) % 2 == 0));
+
+    h.advance(game_id).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert_eq!(board(&game, GameState::is_lava), first);
+    assert_eq!(board(&game, GameState::is_cracking).len(), 12);
+    // Both players stood on even squares
+    assert!(game.players().iter().all(|p| !p.is_alive()));
+
+    // Nothing is left to start cracking, and the game is already decided
+    assert!(game.scheduled_collapse(3).unwrap().is_empty());
+    assert_ignite_error(h.advance(game_id).await, IgniteError::GameAlreadyDecided);
+}
+
+#[tokio::test]
+async fn advance_collapse_needs_a_pattern_and_the_authority() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, _bob) = h.active_game().await;
+    assert_ignite_error(h.advance(game_id).await, IgniteError::ManualCollapsePattern);
+
+    let (game_id, _, _) = pattern_game(&mut h, CollapsePattern::RingShrink).await;
+    let ix = advance_collapse_ix(game_id, &alice.key());
+    assert_anchor_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+}
+
+#[tokio::test]
+async fn cracking_tiles_warn_for_a_round() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+
+    // Bob's tile starts cracking; he can stay a round, and others may step on
+    h.collapse(game_id, vec![(4, 4), (1, 0)]).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert!(game.is_cracking(game.tile(4, 4).unwrap()));
+    assert!(!game.is_lava(game.tile(4, 4).unwrap()));
+    assert!(game.players().iter().all(|p| p.is_alive()));
+    h.move_to(game_id, &alice, 1, 0).await.unwrap();
+
+    // Last round's cracks fall; a tile that just fell isn't cracked again
+    h.collapse(game_id, vec![(1, 0), (0, 0)]).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert!(game.is_lava(game.tile(1, 0).unwrap()));
+    assert!(game.is_cracking(game.tile(0, 0).unwrap()));
+    assert!(!game.is_cracking(game.tile(1, 0).unwrap()));
+    let fallen: Vec<_> = game
+        .players()
+        .iter()
+        .filter(|p| !p.is_alive())
+        .map(|p| (p.pubkey, p.eliminated_in))
+        .collect();
+    assert_eq!(fallen, vec![(alice.key(), 2), (bob.key(), 2)]);
+}
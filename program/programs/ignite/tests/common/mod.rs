@@ -0,0 +1,1057 @@
+//! Offline test harness: runs the compiled `ignite.so` inside
+//! solana-program-test alongside the bundled SPL Token program and a local
+//! mock USDC mint, so tests hit the real SBF runtime (compute, heap and
+//! account-size limits included). Build it first with `cargo build-sbf` or
+//! `anchor build`, or run `cargo test-sbf`, which sets SBF_OUT_DIR.
+//!
+//! Setting IGNITE_TEST_NATIVE=1 runs the program natively through
+//! `processor!` instead: faster and needs no SBF toolchain, but skips
+//! those limits.
+#![allow(dead_code)]
+
+use anchor_lang::{Event, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use base64::prelude::{Engine, BASE64_STANDARD};
+use ignite::{
+    move_commitment, CollapsePattern, ConfigParams, Direction, ForfeitRule, GameState, IgniteError,
+    InitializeGameParams,
+};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use std::{
+    path::PathBuf,
+    sync::{Once, OnceLock},
+};
+
+use solana_sdk::{
+    account_info::AccountInfo,
+    clock::Clock,
+    compute_budget::ComputeBudgetInstruction,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    hash::hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const BUY_IN: u64 = 1_000_000; // 1 USDC
+pub const STARTING_BALANCE: u64 = 10_000_000;
+
+/// Where `cargo build-sbf` / `anchor build` leave `ignite.so`.
+fn default_sbf_out_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/deploy")
+}
+
+fn program_test() -> ProgramTest {
+    if native() {
+        return ProgramTest::new("ignite", ignite::ID, processor!(process_instruction));
+    }
+
+    // solana-program-test searches SBF_OUT_DIR; set it once, before any
+    // test reads it, when not running under `cargo test-sbf`
+    static SET_OUT_DIR: Once = Once::new();
+    SET_OUT_DIR.call_once(|| {
+        if std::env::var_os("BPF_OUT_DIR").is_none() && std::env::var_os("SBF_OUT_DIR").is_none() {
+            std::env::set_var("SBF_OUT_DIR", default_sbf_out_dir());
+        }
+    });
+    let out_dir = std::env::var_os("BPF_OUT_DIR")
+        .or_else(|| std::env::var_os("SBF_OUT_DIR"))
+        .map(PathBuf::from)
+        .unwrap();
+    assert!(
+        out_dir.join("ignite.so").is_file(),
+        "ignite.so not found in {}: run `cargo build-sbf` (or `cargo test-sbf`), \
+         or set IGNITE_TEST_NATIVE=1 to run the program natively",
+        out_dir.display()
+    );
+
+    let mut pt = ProgramTest::new("ignite", ignite::ID, None);
+    pt.prefer_bpf(true);
+    pt
+}
+
+fn native() -> bool {
+    std::env::var_os("IGNITE_TEST_NATIVE").is_some()
+}
+
+// Anchor's entrypoint wants `&'info [AccountInfo<'info>]`, which the
+// builtin-function signature can't promise, so give the slice a long life.
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    ignite::entry(program_id, accounts, data)
+}
+
+static PROGRAM_TEST_STUBS: OnceLock<Box<dyn SyscallStubs>> = OnceLock::new();
+
+/// Natively, program-test's syscall stubs only print `sol_log_data` to
+/// stdout, so `emit!` never reaches the transaction logs. These wrap them
+/// and log each event's data through `sol_log` instead; `events` reads
+/// those lines as well as the SBF runtime's.
+struct NativeStubs;
+
+impl NativeStubs {
+    fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            let stubs = set_syscall_stubs(Box::new(NativeStubs));
+            let _ = PROGRAM_TEST_STUBS.set(stubs);
+        });
+    }
+
+    fn inner(&self) -> &'static dyn SyscallStubs {
+        // Another test may call in between the swap and the store above
+        loop {
+            if let Some(stubs) = PROGRAM_TEST_STUBS.get() {
+                return stubs.as_ref();
+            }
+            std::hint::spin_loop();
+        }
+    }
+}
+
+impl SyscallStubs for NativeStubs {
+    fn sol_log(&self, message: &str) {
+        self.inner().sol_log(message)
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|f| BASE64_STANDARD.encode(f)).collect();
+        self.inner()
+            .sol_log(&format!("Program data: {}", fields.join(" ")))
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.inner()
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_clock_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_epoch_schedule_sysvar(var_addr)
+    }
+
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_epoch_rewards_sysvar(var_addr)
+    }
+
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_fees_sysvar(var_addr)
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_rent_sysvar(var_addr)
+    }
+
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.inner().sol_get_last_restart_slot(var_addr)
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.inner().sol_get_return_data()
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.inner().sol_set_return_data(data)
+    }
+}
+
+pub struct Player {
+    pub wallet: Keypair,
+    pub token: Pubkey,
+}
+
+impl Player {
+    pub fn key(&self) -> Pubkey {
+        self.wallet.pubkey()
+    }
+}
+
+pub struct Harness {
+    pub ctx: ProgramTestContext,
+    pub authority: Keypair,
+    pub mint: Pubkey,
+    /// Owner of the treasury token accounts named in Config
+    pub treasury_wallet: Keypair,
+    /// Treasury token account for `mint`
+    pub treasury: Pubkey,
+    /// Settings last written to Config
+    pub config: ConfigParams,
+    mint_authority: Keypair,
+    nonce: u64,
+    next_game: u8,
+}
+
+pub fn default_params() -> InitializeGameParams {
+    InitializeGameParams {
+        buy_in: BUY_IN,
+        width: 5,
+        height: 5,
+        mask: None,
+        require_cosign: false,
+        cancel_timeout: 3600,
+        min_players: 2,
+        max_players: 4,
+        lobby_timeout: 600,
+        inactivity_timeout: 300,
+        seed_commitment: None,
+        collapse_pattern: CollapsePattern::Manual,
+        forfeit_rule: ForfeitRule::BuyIn,
+        rent_receiver: None,
+        sealed_moves: false,
+    }
+}
+
+pub fn game_pda(game_id: &[u8; 16]) -> Pubkey {
+    Pubkey::find_program_address(&[b"game_state", game_id.as_ref()], &ignite::ID).0
+}
+
+pub fn escrow_pda(game_id: &[u8; 16]) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow", game_id.as_ref()], &ignite::ID).0
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &ignite::ID).0
+}
+
+fn ix(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ignite::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn with_remaining(mut ix: Instruction, remaining: &[Pubkey]) -> Instruction {
+    ix.accounts
+        .extend(remaining.iter().map(|k| AccountMeta::new(*k, false)));
+    ix
+}
+
+// ─── Instruction Builders ─────────────────────────────────────────────────────
+
+pub fn initialize_config_ix(admin: &Pubkey, params: ConfigParams) -> Instruction {
+    ix(
+        ignite::accounts::InitializeConfig {
+            config: config_pda(),
+            admin: *admin,
+            system_program: solana_sdk::system_program::ID,
+        },
+        ignite::instruction::InitializeConfig { params },
+    )
+}
+
+pub fn update_config_ix(admin: &Pubkey, params: ConfigParams) -> Instruction {
+    ix(
+        ignite::accounts::UpdateConfig {
+            config: config_pda(),
+            admin: *admin,
+        },
+        ignite::instruction::UpdateConfig { params },
+    )
+}
+
+pub fn set_paused_ix(admin: &Pubkey, paused: bool) -> Instruction {
+    ix(
+        ignite::accounts::UpdateConfig {
+            config: config_pda(),
+            admin: *admin,
+        },
+        ignite::instruction::SetPaused { paused },
+    )
+}
+
+pub fn rotate_admin_ix(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    ix(
+        ignite::accounts::RotateAdmin {
+            config: config_pda(),
+            admin: *admin,
+            new_admin: *new_admin,
+        },
+        ignite::instruction::RotateAdmin {},
+    )
+}
+
+pub fn allocate_game_ix(authority: &Pubkey, game_id: [u8; 16]) -> Instruction {
+    ix(
+        ignite::accounts::AllocateGame {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            authority: *authority,
+            system_program: solana_sdk::system_program::ID,
+        },
+        ignite::instruction::AllocateGame { game_id },
+    )
+}
+
+pub fn initialize_game_ix(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    game_id: [u8; 16],
+    params: InitializeGameParams,
+) -> Instruction {
+    ix(
+        ignite::accounts::InitializeGame {
+            game_state: game_pda(&game_id),
+            escrow_vault: escrow_pda(&game_id),
+            config: config_pda(),
+            mint: *mint,
+            authority: *authority,
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        ignite::instruction::InitializeGame { game_id, params },
+    )
+}
+
+/// Enough `allocate_game` calls to size the GameState PDA, then
+/// `initialize_game`, for one transaction.
+pub fn create_game_ixs(
+    authority: &Pubkey,
+    mint: &Pubkey,
+    game_id: [u8; 16],
+    params: InitializeGameParams,
+) -> Vec<Instruction> {
+    let allocations = GameState::SIZE.div_ceil(MAX_PERMITTED_DATA_INCREASE);
+    let mut ixs = vec![allocate_game_ix(authority, game_id); allocations];
+    ixs.push(initialize_game_ix(authority, mint, game_id, params));
+    ixs
+}
+
+pub fn join_game_ix(
+    game_id: [u8; 16],
+    player: &Pubkey,
+    player_token_account: &Pubkey,
+    session_key: Option<Pubkey>,
+    start_x: u8,
+    start_y: u8,
+    entropy_commitment: Option<[u8; 32]>,
+) -> Instruction {
+    ix(
+        ignite::accounts::JoinGame {
+            game_state: game_pda(&game_id),
+            escrow_vault: escrow_pda(&game_id),
+            player_token_account: *player_token_account,
+            config: config_pda(),
+            player: *player,
+            session_key,
+            token_program: spl_token::ID,
+        },
+        ignite::instruction::JoinGame {
+            _game_id: game_id,
+            start_x,
+            start_y,
+            entropy_commitment,
+        },
+    )
+}
+
+pub fn leave_game_ix(
+    game_id: [u8; 16],
+    player: &Pubkey,
+    player_token_account: &Pubkey,
+) -> Instruction {
+    ix(
+        ignite::accounts::LeaveGame {
+            game_state: game_pda(&game_id),
+            escrow_vault: escrow_pda(&game_id),
+            player_token_account: *player_token_account,
+            player: *player,
+            token_program: spl_token::ID,
+        },
+        ignite::instruction::LeaveGame { game_id },
+    )
+}
+
+pub fn start_game_ix(game_id: [u8; 16], caller: &Pubkey, refunds: &[Pubkey]) -> Instruction {
+    with_remaining(
+        ix(
+            ignite::accounts::StartGame {
+                game_state: game_pda(&game_id),
+                escrow_vault: escrow_pda(&game_id),
+                config: config_pda(),
+                caller: *caller,
+                token_program: spl_token::ID,
+            },
+            ignite::instruction::StartGame { game_id },
+        ),
+        refunds,
+    )
+}
+
+pub fn lock_lobby_ix(game_id: [u8; 16], caller: &Pubkey) -> Instruction {
+    ix(
+        ignite::accounts::LockLobby {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            caller: *caller,
+        },
+        ignite::instruction::LockLobby { game_id },
+    )
+}
+
+pub fn reveal_entropy_ix(game_id: [u8; 16], player: &Pubkey, entropy: [u8; 32]) -> Instruction {
+    ix(
+        ignite::accounts::RevealEntropy {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            player: *player,
+        },
+        ignite::instruction::RevealEntropy { game_id, entropy },
+    )
+}
+
+pub fn submit_move_ix(
+    game_id: [u8; 16],
+    player: &Pubkey,
+    authority: Option<Pubkey>,
+    new_x: u8,
+    new_y: u8,
+) -> Instruction {
+    ix(
+        ignite::accounts::SubmitMove {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            player: *player,
+            authority,
+        },
+        ignite::instruction::SubmitMove {
+            _game_id: game_id,
+            new_x,
+            new_y,
+        },
+    )
+}
+
+pub fn commit_move_ix(
+    game_id: [u8; 16],
+    player: &Pubkey,
+    authority: Option<Pubkey>,
+    commitment: [u8; 32],
+) -> Instruction {
+    ix(
+        ignite::accounts::SubmitMove {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            player: *player,
+            authority,
+        },
+        ignite::instruction::CommitMove {
+            game_id,
+            commitment,
+        },
+    )
+}
+
+pub fn open_reveals_ix(game_id: [u8; 16], authority: &Pubkey) -> Instruction {
+    ix(
+        ignite::accounts::TriggerCollapse {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            authority: *authority,
+        },
+        ignite::instruction::OpenReveals { game_id },
+    )
+}
+
+pub fn reveal_move_ix(
+    game_id: [u8; 16],
+    player: &Pubkey,
+    direction: Direction,
+    salt: [u8; 32],
+) -> Instruction {
+    ix(
+        ignite::accounts::RevealMove {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            player: *player,
+        },
+        ignite::instruction::RevealMove {
+            game_id,
+            direction,
+            salt,
+        },
+    )
+}
+
+pub fn resolve_moves_ix(game_id: [u8; 16], authority: &Pubkey) -> Instruction {
+    ix(
+        ignite::accounts::TriggerCollapse {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            authority: *authority,
+        },
+        ignite::instruction::ResolveMoves { game_id },
+    )
+}
+
+pub fn reveal_seed_ix(game_id: [u8; 16], authority: &Pubkey, seed: [u8; 32]) -> Instruction {
+    ix(
+        ignite::accounts::RevealSeed {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            authority: *authority,
+        },
+        ignite::instruction::RevealSeed {
+            _game_id: game_id,
+            seed,
+        },
+    )
+}
+
+pub fn trigger_collapse_ix(
+    game_id: [u8; 16],
+    authority: &Pubkey,
+    tiles: Vec<(u8, u8)>,
+) -> Instruction {
+    ix(
+        ignite::accounts::TriggerCollapse {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            authority: *authority,
+        },
+        ignite::instruction::TriggerCollapse {
+            _game_id: game_id,
+            tiles,
+        },
+    )
+}
+
+pub fn advance_collapse_ix(game_id: [u8; 16], authority: &Pubkey) -> Instruction {
+    ix(
+        ignite::accounts::TriggerCollapse {
+            game_state: game_pda(&game_id),
+            config: config_pda(),
+            authority: *authority,
+        },
+        ignite::instruction::AdvanceCollapse { _game_id: game_id },
+    )
+}
+
+pub fn declare_winner_ix(
+    game_id: [u8; 16],
+    authority: &Pubkey,
+    winner_token_account: &Pubkey,
+    treasury_token_account: &Pubkey,
+) -> Instruction {
+    ix(
+        ignite::accounts::DeclareWinner {
+            game_state: game_pda(&game_id),
+            escrow_vault: escrow_pda(&game_id),
+            winner_token_account: *winner_token_account,
+            config: config_pda(),
+            treasury_token_account: *treasury_token_account,
+            authority: *authority,
+            token_program: spl_token::ID,
+        },
+        ignite::instruction::DeclareWinner { game_id },
+    )
+}
+
+pub fn declare_draw_ix(game_id: [u8; 16], authority: &Pubkey, payouts: &[Pubkey]) -> Instruction {
+    with_remaining(
+        ix(
+            ignite::accounts::DeclareDraw {
+                game_state: game_pda(&game_id),
+                escrow_vault: escrow_pda(&game_id),
+                config: config_pda(),
+                authority: *authority,
+                token_program: spl_token::ID,
+            },
+            ignite::instruction::DeclareDraw { game_id },
+        ),
+        payouts,
+    )
+}
+
+pub fn expire_game_ix(game_id: [u8; 16], caller: &Pubkey, payouts: &[Pubkey]) -> Instruction {
+    with_remaining(
+        ix(
+            ignite::accounts::ExpireGame {
+                game_state: game_pda(&game_id),
+                escrow_vault: escrow_pda(&game_id),
+                config: config_pda(),
+                caller: *caller,
+                token_program: spl_token::ID,
+            },
+            ignite::instruction::ExpireGame { game_id },
+        ),
+        payouts,
+    )
+}
+
+pub fn cancel_game_ix(game_id: [u8; 16], caller: &Pubkey, refunds: &[Pubkey]) -> Instruction {
+    with_remaining(
+        ix(
+            ignite::accounts::CancelGame {
+                game_state: game_pda(&game_id),
+                escrow_vault: escrow_pda(&game_id),
+                caller: *caller,
+                token_program: spl_token::ID,
+            },
+            ignite::instruction::CancelGame { game_id },
+        ),
+        refunds,
+    )
+}
+
+pub fn pay_out_ix(game_id: [u8; 16], start: u8, payouts: &[Pubkey]) -> Instruction {
+    with_remaining(
+        ix(
+            ignite::accounts::PayOut {
+                game_state: game_pda(&game_id),
+                escrow_vault: escrow_pda(&game_id),
+                token_program: spl_token::ID,
+            },
+            ignite::instruction::PayOut {
+                _game_id: game_id,
+                start,
+            },
+        ),
+        payouts,
+    )
+}
+
+pub fn emergency_refund_ix(game_id: [u8; 16], admin: &Pubkey, refunds: &[Pubkey]) -> Instruction {
+    with_remaining(
+        ix(
+            ignite::accounts::EmergencyRefund {
+                game_state: game_pda(&game_id),
+                escrow_vault: escrow_pda(&game_id),
+                config: config_pda(),
+                admin: *admin,
+                token_program: spl_token::ID,
+            },
+            ignite::instruction::EmergencyRefund { game_id },
+        ),
+        refunds,
+    )
+}
+
+pub fn close_game_ix(game_id: [u8; 16], authority: &Pubkey, rent_receiver: &Pubkey) -> Instruction {
+    ix(
+        ignite::accounts::CloseGame {
+            game_state: game_pda(&game_id),
+            escrow_vault: escrow_pda(&game_id),
+            rent_receiver: *rent_receiver,
+            authority: *authority,
+            token_program: spl_token::ID,
+        },
+        ignite::instruction::CloseGame { game_id },
+    )
+}
+
+// ─── Harness ──────────────────────────────────────────────────────────────────
+
+impl Harness {
+    pub async fn new() -> Self {
+        let ctx = program_test().start_with_context().await;
+        if native() {
+            // After the start above has installed program-test's stubs
+            NativeStubs::install();
+        }
+        let authority = ctx.payer.insecure_clone();
+        let mint_authority = Keypair::new();
+        let mut h = Harness {
+            ctx,
+            authority,
+            mint: Pubkey::default(),
+            treasury_wallet: Keypair::new(),
+            treasury: Pubkey::default(),
+            config: ConfigParams {
+                fee_bps: 0,
+                treasury: Pubkey::default(),
+                authorities: vec![],
+                allowed_mints: vec![],
+                min_buy_in: 1,
+                max_buy_in: 100 * BUY_IN,
+            },
+            mint_authority,
+            nonce: 0,
+            next_game: 0,
+        };
+        h.mint = h.create_mint().await;
+
+        // The authority doubles as Config admin and the only approved game
+        // authority; no rake unless a test sets one
+        let (mint, treasury_wallet) = (h.mint, h.treasury_wallet.pubkey());
+        h.treasury = h.create_token_account(&mint, &treasury_wallet).await;
+        h.config.treasury = treasury_wallet;
+        h.config.authorities = vec![h.authority.pubkey()];
+        h.config.allowed_mints = vec![mint];
+        let ix = initialize_config_ix(&h.authority.pubkey(), h.config.clone());
+        h.send(&[ix], &[]).await.unwrap();
+        h
+    }
+
+    pub async fn update_config(&mut self, params: ConfigParams) -> Result<(), BanksClientError> {
+        let ix = update_config_ix(&self.authority.pubkey(), params.clone());
+        self.send(&[ix], &[]).await?;
+        self.config = params;
+        Ok(())
+    }
+
+    pub async fn set_paused(&mut self, paused: bool) -> Result<(), BanksClientError> {
+        let ix = set_paused_ix(&self.authority.pubkey(), paused);
+        self.send(&[ix], &[]).await
+    }
+
+    /// Send `ixs` paid for by the authority, signed additionally by `signers`.
+    pub async fn send(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let tx = self.transaction(ixs, signers).await;
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Like `send`, but returns the transaction's log messages, which carry
+    /// its events; decode them with `events`.
+    pub async fn send_logged(
+        &mut self,
+        ixs: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Vec<String>, BanksClientError> {
+        let tx = self.transaction(ixs, signers).await;
+        let outcome = self
+            .ctx
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await?;
+        outcome.result.map_err(BanksClientError::TransactionError)?;
+        Ok(outcome.metadata.unwrap().log_messages)
+    }
+
+    async fn transaction(&mut self, ixs: &[Instruction], signers: &[&Keypair]) -> Transaction {
+        // A distinct priority fee per transaction keeps signatures unique,
+        // so retrying an identical instruction isn't dropped as a duplicate.
+        self.nonce += 1;
+        let mut all = vec![ComputeBudgetInstruction::set_compute_unit_price(self.nonce)];
+        all.extend_from_slice(ixs);
+
+        let payer = self.ctx.payer.insecure_clone();
+        let mut keys: Vec<&Keypair> = vec![&payer];
+        keys.extend(signers.iter().filter(|k| k.pubkey() != payer.pubkey()));
+
+        let blockhash = self.ctx.banks_client.get_latest_blockhash().await.unwrap();
+        Transaction::new_signed_with_payer(&all, Some(&payer.pubkey()), &keys, blockhash)
+    }
+
+    pub async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let ixs = [
+            system_instruction::create_account(
+                &self.ctx.payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &self.mint_authority.pubkey(),
+                None,
+                6,
+            )
+            .unwrap(),
+        ];
+        self.send(&ixs, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    pub async fn create_token_account(&mut self, mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+        let ixs = [
+            system_instruction::create_account(
+                &self.ctx.payer.pubkey(),
+                &account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::ID,
+                &account.pubkey(),
+                mint,
+                owner,
+            )
+            .unwrap(),
+        ];
+        self.send(&ixs, &[&account]).await.unwrap();
+        account.pubkey()
+    }
+
+    pub async fn mint_to(&mut self, mint: &Pubkey, account: &Pubkey, amount: u64) {
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            mint,
+            account,
+            &self.mint_authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        let mint_authority = self.mint_authority.insecure_clone();
+        self.send(&[ix], &[&mint_authority]).await.unwrap();
+    }
+
+    /// A fresh wallet holding `STARTING_BALANCE` of the game mint.
+    pub async fn new_player(&mut self) -> Player {
+        let wallet = Keypair::new();
+        let mint = self.mint;
+        let token = self.create_token_account(&mint, &wallet.pubkey()).await;
+        self.mint_to(&mint, &token, STARTING_BALANCE).await;
+        Player { wallet, token }
+    }
+
+    pub fn next_game_id(&mut self) -> [u8; 16] {
+        self.next_game += 1;
+        [self.next_game; 16]
+    }
+
+    pub async fn init_game(&mut self, params: InitializeGameParams) -> [u8; 16] {
+        let game_id = self.next_game_id();
+        let ixs = create_game_ixs(&self.authority.pubkey(), &self.mint, game_id, params);
+        self.send(&ixs, &[]).await.unwrap();
+        game_id
+    }
+
+    pub async fn join(
+        &mut self,
+        game_id: [u8; 16],
+        player: &Player,
+        x: u8,
+        y: u8,
+    ) -> Result<(), BanksClientError> {
+        let ix = join_game_ix(game_id, &player.key(), &player.token, None, x, y, None);
+        self.send(&[ix], &[&player.wallet]).await
+    }
+
+    /// Join a seeded game, committing to `entropy`.
+    pub async fn join_committed(
+        &mut self,
+        game_id: [u8; 16],
+        player: &Player,
+        x: u8,
+        y: u8,
+        entropy: [u8; 32],
+    ) -> Result<(), BanksClientError> {
+        let commitment = Some(hash(&entropy).to_bytes());
+        let ix = join_game_ix(
+            game_id,
+            &player.key(),
+            &player.token,
+            None,
+            x,
+            y,
+            commitment,
+        );
+        self.send(&[ix], &[&player.wallet]).await
+    }
+
+    pub async fn lock_lobby(&mut self, game_id: [u8; 16]) -> Result<(), BanksClientError> {
+        let ix = lock_lobby_ix(game_id, &self.authority.pubkey());
+        self.send(&[ix], &[]).await
+    }
+
+    pub async fn reveal_entropy(
+        &mut self,
+        game_id: [u8; 16],
+        player: &Player,
+        entropy: [u8; 32],
+    ) -> Result<(), BanksClientError> {
+        let ix = reveal_entropy_ix(game_id, &player.key(), entropy);
+        self.send(&[ix], &[&player.wallet]).await
+    }
+
+    pub async fn start(&mut self, game_id: [u8; 16]) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        self.send(&[start_game_ix(game_id, &authority.pubkey(), &[])], &[])
+            .await
+    }
+
+    pub async fn move_to(
+        &mut self,
+        game_id: [u8; 16],
+        player: &Player,
+        x: u8,
+        y: u8,
+    ) -> Result<(), BanksClientError> {
+        let ix = submit_move_ix(game_id, &player.key(), None, x, y);
+        self.send(&[ix], &[&player.wallet]).await
+    }
+
+    /// Commit `direction` under the player's salt (their key's bytes).
+    pub async fn commit_move(
+        &mut self,
+        game_id: [u8; 16],
+        player: &Player,
+        direction: Direction,
+    ) -> Result<(), BanksClientError> {
+        let commitment = move_commitment(direction, &player.key().to_bytes());
+        let ix = commit_move_ix(game_id, &player.key(), None, commitment);
+        self.send(&[ix], &[&player.wallet]).await
+    }
+
+    pub async fn reveal_move(
+        &mut self,
+        game_id: [u8; 16],
+        player: &Player,
+        direction: Direction,
+    ) -> Result<(), BanksClientError> {
+        let ix = reveal_move_ix(game_id, &player.key(), direction, player.key().to_bytes());
+        self.send(&[ix], &[&player.wallet]).await
+    }
+
+    pub async fn open_reveals(&mut self, game_id: [u8; 16]) -> Result<(), BanksClientError> {
+        let ix = open_reveals_ix(game_id, &self.authority.pubkey());
+        self.send(&[ix], &[]).await
+    }
+
+    pub async fn resolve_moves(&mut self, game_id: [u8; 16]) -> Result<(), BanksClientError> {
+        let ix = resolve_moves_ix(game_id, &self.authority.pubkey());
+        self.send(&[ix], &[]).await
+    }
+
+    pub async fn collapse(
+        &mut self,
+        game_id: [u8; 16],
+        tiles: Vec<(u8, u8)>,
+    ) -> Result<(), BanksClientError> {
+        let ix = trigger_collapse_ix(game_id, &self.authority.pubkey(), tiles);
+        self.send(&[ix], &[]).await
+    }
+
+    /// Start `tiles` cracking, then run an empty round so they turn to lava.
+    pub async fn collapse_to_lava(
+        &mut self,
+        game_id: [u8; 16],
+        tiles: Vec<(u8, u8)>,
+    ) -> Result<(), BanksClientError> {
+        self.collapse(game_id, tiles).await?;
+        self.collapse(game_id, vec![]).await
+    }
+
+    pub async fn advance(&mut self, game_id: [u8; 16]) -> Result<(), BanksClientError> {
+        let ix = advance_collapse_ix(game_id, &self.authority.pubkey());
+        self.send(&[ix], &[]).await
+    }
+
+    /// A started game with two players at (0,0) and (4,4).
+    pub async fn active_game(&mut self) -> ([u8; 16], Player, Player) {
+        let game_id = self.init_game(default_params()).await;
+        let alice = self.new_player().await;
+        let bob = self.new_player().await;
+        self.join(game_id, &alice, 0, 0).await.unwrap();
+        self.join(game_id, &bob, 4, 4).await.unwrap();
+        self.start(game_id).await.unwrap();
+        (game_id, alice, bob)
+    }
+
+    pub async fn game(&mut self, game_id: &[u8; 16]) -> GameState {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(game_pda(game_id))
+            .await
+            .unwrap()
+            .expect("game state account");
+        bytemuck::pod_read_unaligned(&account.data[8..GameState::SIZE])
+    }
+
+    pub async fn lamports(&mut self, pubkey: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*pubkey).await.unwrap()
+    }
+
+    pub async fn exists(&mut self, pubkey: &Pubkey) -> bool {
+        self.ctx
+            .banks_client
+            .get_account(*pubkey)
+            .await
+            .unwrap()
+            .is_some()
+    }
+
+    pub async fn balance(&mut self, token_account: &Pubkey) -> u64 {
+        let account = self
+            .ctx
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+            .expect("token account");
+        spl_token::state::Account::unpack(&account.data)
+            .unwrap()
+            .amount
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.ctx.banks_client.get_sysvar::<Clock>().await.unwrap()
+    }
+
+    pub async fn advance_clock(&mut self, seconds: i64) {
+        let mut clock = self.clock().await;
+        clock.unix_timestamp += seconds;
+        self.ctx.set_sysvar(&clock);
+    }
+}
+
+// ─── Events ───────────────────────────────────────────────────────────────────
+
+/// Every `E` in a transaction's logs, decoded from the `Program data:`
+/// lines `emit!` writes (`Program log: Program data:` when native).
+pub fn events<E: Event>(logs: &[String]) -> Vec<E> {
+    logs.iter()
+        .map(|line| line.strip_prefix("Program log: ").unwrap_or(line))
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .map(|data| BASE64_STANDARD.decode(data).unwrap())
+        .filter(|data| data.starts_with(&E::DISCRIMINATOR))
+        .map(|data| E::try_from_slice(&data[8..]).unwrap())
+        .collect()
+}
+
+// ─── Assertions ───────────────────────────────────────────────────────────────
+
+pub fn assert_custom_error(result: Result<(), BanksClientError>, code: u32) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected custom error {code}, got {actual}"),
+        other => panic!("expected custom error {code}, got {other:?}"),
+    }
+}
+
+pub fn assert_ignite_error(result: Result<(), BanksClientError>, error: IgniteError) {
+    assert_custom_error(result, error.into());
+}
+
+pub fn assert_anchor_error(
+    result: Result<(), BanksClientError>,
+    error: anchor_lang::error::ErrorCode,
+) {
+    assert_custom_error(result, error.into());
+}
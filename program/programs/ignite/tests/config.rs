@@ -0,0 +1,323 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use ignite::{
+    AdminRotated, ConfigParams, ConfigUpdated, GameStatus, IgniteError, InitializeGameParams,
+    ProgramPaused,
+};
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn update_config_is_admin_only_and_validated() {
+    let mut h = Harness::new().await;
+
+    let stranger = Keypair::new();
+    let ix = update_config_ix(&stranger.pubkey(), h.config.clone());
+    assert_anchor_error(
+        h.send(&[ix], &[&stranger]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+
+    let cases = [
+        (
+            ConfigParams {
+                fee_bps: 10_001,
+                ..h.config.clone()
+            },
+            IgniteError::InvalidFee,
+        ),
+        (
+            ConfigParams {
+                min_buy_in: 10,
+                max_buy_in: 9,
+                ..h.config.clone()
+            },
+            IgniteError::InvalidBuyInRange,
+        ),
+        (
+            ConfigParams {
+                allowed_mints: vec![Pubkey::new_unique(); 9],
+                ..h.config.clone()
+            },
+            IgniteError::ConfigListTooLong,
+        ),
+    ];
+    for (params, error) in cases {
+        assert_ignite_error(h.update_config(params).await, error);
+    }
+
+    let admin = h.authority.pubkey();
+    let params = ConfigParams {
+        fee_bps: 250,
+        ..h.config.clone()
+    };
+    let ix = update_config_ix(&admin, params);
+    let logs = h.send_logged(&[ix], &[]).await.unwrap();
+    let [updated] = &events::<ConfigUpdated>(&logs)[..] else {
+        panic!("expected one ConfigUpdated in {logs:?}");
+    };
+    assert_eq!((updated.admin, updated.fee_bps), (admin, 250));
+    assert_eq!(updated.authorities, h.config.authorities);
+    let game_id = h.init_game(default_params()).await;
+    assert_eq!(h.game(&game_id).await.fee_bps, 250);
+}
+
+#[tokio::test]
+async fn initialize_game_enforces_config() {
+    let mut h = Harness::new().await;
+    let mint = h.mint;
+
+    // Only approved authorities may create games
+    let rogue = Keypair::new();
+    let fund = solana_sdk::system_instruction::transfer(
+        &h.authority.pubkey(),
+        &rogue.pubkey(),
+        1_000_000_000,
+    );
+    h.send(&[fund], &[]).await.unwrap();
+    let game_id = h.next_game_id();
+    let ixs = create_game_ixs(&rogue.pubkey(), &mint, game_id, default_params());
+    assert_ignite_error(
+        h.send(&ixs, &[&rogue]).await,
+        IgniteError::UnapprovedAuthority,
+    );
+
+    let authority = h.authority.pubkey();
+    let other_mint = h.create_mint().await;
+    let game_id = h.next_game_id();
+    let ixs = create_game_ixs(&authority, &other_mint, game_id, default_params());
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::MintNotAllowed);
+
+    let max_buy_in = h.config.max_buy_in;
+    for buy_in in [0, max_buy_in + 1] {
+        let game_id = h.next_game_id();
+        let params = InitializeGameParams {
+            buy_in,
+            ..default_params()
+        };
+        let ixs = create_game_ixs(&authority, &mint, game_id, params);
+        assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::BuyInOutOfRange);
+    }
+}
+
+#[tokio::test]
+async fn pause_blocks_new_games_and_joins() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+
+    let admin = h.authority.pubkey();
+    let ix = set_paused_ix(&admin, true);
+    let logs = h.send_logged(&[ix], &[]).await.unwrap();
+    assert!(events::<ProgramPaused>(&logs)[0].paused);
+    assert_ignite_error(
+        h.join(game_id, &alice, 0, 0).await,
+        IgniteError::ProgramPaused,
+    );
+    let authority = h.authority.pubkey();
+    let mint = h.mint;
+    let ixs = create_game_ixs(&authority, &mint, h.next_game_id(), default_params());
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::ProgramPaused);
+
+    let ix = set_paused_ix(&admin, false);
+    let logs = h.send_logged(&[ix], &[]).await.unwrap();
+    assert!(!events::<ProgramPaused>(&logs)[0].paused);
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+}
+
+#[tokio::test]
+async fn pause_blocks_gameplay_but_not_refunds() {
+    let mut h = Harness::new().await;
+    let authority = h.authority.pubkey();
+    let (game_id, alice, bob) = h.active_game().await;
+
+    let lobby = h.init_game(default_params()).await;
+    let carol = h.new_player().await;
+    let dave = h.new_player().await;
+    h.join(lobby, &carol, 0, 0).await.unwrap();
+    h.join(lobby, &dave, 1, 0).await.unwrap();
+
+    h.set_paused(true).await.unwrap();
+    assert_ignite_error(h.start(lobby).await, IgniteError::ProgramPaused);
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 0).await,
+        IgniteError::ProgramPaused,
+    );
+    assert_ignite_error(
+        h.collapse(game_id, vec![(4, 4)]).await,
+        IgniteError::ProgramPaused,
+    );
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::ProgramPaused);
+    let ix = declare_draw_ix(game_id, &authority, &[alice.token, bob.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::ProgramPaused);
+
+    // The authority can't collapse, so the inactivity clock can't run out
+    h.advance_clock(default_params().inactivity_timeout + 1)
+        .await;
+    let ix = expire_game_ix(game_id, &carol.key(), &[alice.token, bob.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[&carol.wallet]).await,
+        IgniteError::ProgramPaused,
+    );
+
+    // Players can still get out of a lobby
+    let ix = leave_game_ix(lobby, &dave.key(), &dave.token);
+    h.send(&[ix], &[&dave.wallet]).await.unwrap();
+    let ix = cancel_game_ix(lobby, &authority, &[carol.token]);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_eq!(h.balance(&carol.token).await, STARTING_BALANCE);
+}
+
+#[tokio::test]
+async fn unpausing_restarts_the_inactivity_clock() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+    let timeout = default_params().inactivity_timeout;
+    h.set_paused(true).await.unwrap();
+    h.advance_clock(2 * timeout).await;
+    h.set_paused(false).await.unwrap();
+
+    // The authority gets a full timeout after the unpause to act again
+    let stranger = Keypair::new();
+    let payouts = [alice.token, bob.token];
+    h.advance_clock(timeout - 10).await;
+    let ix = expire_game_ix(game_id, &stranger.pubkey(), &payouts);
+    assert_ignite_error(
+        h.send(&[ix], &[&stranger]).await,
+        IgniteError::InactivityTimeoutNotReached,
+    );
+    h.advance_clock(20).await;
+    let ix = expire_game_ix(game_id, &stranger.pubkey(), &payouts);
+    h.send(&[ix], &[&stranger]).await.unwrap();
+    assert_eq!(h.game(&game_id).await.status(), GameStatus::Expired);
+}
+
+#[tokio::test]
+async fn emergency_refund_splits_escrow_while_paused() {
+    let mut h = Harness::new().await;
+    let admin = h.authority.pubkey();
+    let (game_id, alice, bob) = h.active_game().await;
+    h.collapse(game_id, vec![(4, 4)]).await.unwrap();
+
+    let refunds = [alice.token, bob.token];
+    let ix = emergency_refund_ix(game_id, &admin, &refunds);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::ProgramNotPaused);
+
+    h.set_paused(true).await.unwrap();
+
+    let stranger = Keypair::new();
+    let ix = emergency_refund_ix(game_id, &stranger.pubkey(), &refunds);
+    assert_anchor_error(
+        h.send(&[ix], &[&stranger]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+    let ix = emergency_refund_ix(game_id, &admin, &[alice.token, bob.token, alice.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::RefundAccountsMismatch,
+    );
+    let ix = emergency_refund_ix(game_id, &admin, &[bob.token, alice.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::InvalidRefundAccount);
+
+    // A stray unit in the vault is swept too, to the first player
+    let (mint, escrow) = (h.mint, escrow_pda(&game_id));
+    h.mint_to(&mint, &escrow, 1).await;
+
+    let ix = emergency_refund_ix(game_id, &admin, &refunds);
+    h.send(&[ix], &[]).await.unwrap();
+
+    // Eliminated players get their share back as well
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE + 1);
+    assert_eq!(h.balance(&bob.token).await, STARTING_BALANCE);
+    assert_eq!(h.balance(&escrow).await, 0);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.status(), GameStatus::Refunded);
+    assert_eq!(game.prize_pool, 0);
+
+    let ix = emergency_refund_ix(game_id, &admin, &refunds);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::InvalidStatusTransition,
+    );
+
+    let ix = close_game_ix(game_id, &admin, &admin);
+    h.send(&[ix], &[]).await.unwrap();
+    assert!(!h.exists(&game_pda(&game_id)).await);
+}
+
+#[tokio::test]
+async fn rotate_admin_requires_both_keys() {
+    let mut h = Harness::new().await;
+    let admin = h.authority.pubkey();
+    let new_admin = Keypair::new();
+
+    let stranger = Keypair::new();
+    let ix = rotate_admin_ix(&stranger.pubkey(), &new_admin.pubkey());
+    assert_anchor_error(
+        h.send(&[ix], &[&stranger, &new_admin]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+
+    let ix = rotate_admin_ix(&admin, &new_admin.pubkey());
+    let logs = h.send_logged(&[ix], &[&new_admin]).await.unwrap();
+    let [rotated] = &events::<AdminRotated>(&logs)[..] else {
+        panic!("expected one AdminRotated in {logs:?}");
+    };
+    assert_eq!(
+        (rotated.old_admin, rotated.new_admin),
+        (admin, new_admin.pubkey())
+    );
+
+    // The old admin is locked out; the new one is in charge
+    assert_anchor_error(h.set_paused(true).await, ErrorCode::ConstraintHasOne);
+    let ix = set_paused_ix(&new_admin.pubkey(), true);
+    h.send(&[ix], &[&new_admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn declare_winner_takes_rake_for_treasury() {
+    let mut h = Harness::new().await;
+    let admin = h.authority.pubkey();
+
+    // Games keep the fee they were created with
+    let (old_game, old_alice, _) = h.active_game().await;
+    h.update_config(ConfigParams {
+        fee_bps: 500,
+        ..h.config.clone()
+    })
+    .await
+    .unwrap();
+    let (game_id, alice, bob) = h.active_game().await;
+
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+
+    // Fee must go to a token account owned by the configured treasury
+    let ix = declare_winner_ix(game_id, &admin, &alice.token, &bob.token);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::InvalidTreasuryAccount,
+    );
+
+    let ix = declare_winner_ix(game_id, &admin, &alice.token, &h.treasury);
+    h.send(&[ix], &[]).await.unwrap();
+
+    let pot = 2 * BUY_IN;
+    let fee = pot * 500 / 10_000;
+    let treasury = h.treasury;
+    assert_eq!(h.balance(&treasury).await, fee);
+    assert_eq!(
+        h.balance(&alice.token).await,
+        STARTING_BALANCE - BUY_IN + pot - fee
+    );
+    let game = h.game(&game_id).await;
+    assert_eq!(game.fee_paid, fee);
+    assert_eq!(game.prize_pool, 0);
+
+    h.collapse_to_lava(old_game, vec![(4, 4)]).await.unwrap();
+    let ix = declare_winner_ix(old_game, &admin, &old_alice.token, &h.treasury);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_eq!(h.balance(&old_alice.token).await, STARTING_BALANCE + BUY_IN);
+    assert_eq!(h.game(&old_game).await.fee_paid, 0);
+}
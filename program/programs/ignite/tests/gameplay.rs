@@ -0,0 +1,863 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use ignite::{
+    collapse_schedule, CollapsePattern, ForfeitRule, GameStatus, IgniteError, InitializeGameParams,
+    TilesCollapsed, WinnerDeclared,
+};
+use solana_sdk::{
+    hash::{hash, hashv},
+    signature::Keypair,
+    signer::Signer,
+};
+
+#[tokio::test]
+async fn full_match_pays_the_winner() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+
+    h.move_to(game_id, &alice, 1, 0).await.unwrap();
+    h.move_to(game_id, &bob, 4, 3).await.unwrap();
+    h.collapse(game_id, vec![(0, 0), (4, 4)]).await.unwrap();
+    h.collapse(game_id, vec![(4, 3)]).await.unwrap();
+    let authority = h.authority.pubkey();
+    let ix = trigger_collapse_ix(game_id, &authority, vec![]);
+    let logs = h.send_logged(&[ix], &[]).await.unwrap();
+
+    let [collapsed] = &events::<TilesCollapsed>(&logs)[..] else {
+        panic!("expected one TilesCollapsed in {logs:?}");
+    };
+    assert_eq!(collapsed.game_id, game_id);
+    assert_eq!(collapsed.round, 3);
+    assert!(collapsed.tiles.is_empty());
+    assert_eq!(collapsed.eliminated, [bob.key()]);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.collapse_round, 3);
+    assert!(game.players[0].is_alive());
+    assert!(!game.players[1].is_alive());
+    assert_eq!(game.players[1].eliminated_in, 3);
+
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    let logs = h.send_logged(&[ix], &[]).await.unwrap();
+    let [declared] = &events::<WinnerDeclared>(&logs)[..] else {
+        panic!("expected one WinnerDeclared in {logs:?}");
+    };
+    assert_eq!(declared.winner, alice.key());
+    assert_eq!((declared.payout, declared.fee), (2 * BUY_IN, 0));
+
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE + BUY_IN);
+    assert_eq!(h.balance(&bob.token).await, STARTING_BALANCE - BUY_IN);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, 0);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.status(), GameStatus::Resolved);
+    assert_eq!(game.winner(), Some(alice.key()));
+    assert_eq!(game.prize_pool, 0);
+
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::InvalidStatusTransition,
+    );
+}
+
+#[tokio::test]
+async fn submit_move_rejects_invalid_moves() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 4, 4).await.unwrap();
+
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 0).await,
+        IgniteError::GameNotActive,
+    );
+    h.start(game_id).await.unwrap();
+
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 1).await,
+        IgniteError::InvalidMove,
+    );
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 2, 0).await,
+        IgniteError::InvalidMove,
+    );
+    assert_ignite_error(
+        h.move_to(game_id, &bob, 5, 4).await,
+        IgniteError::OutOfBounds,
+    );
+
+    h.collapse_to_lava(game_id, vec![(1, 0)]).await.unwrap();
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 0).await,
+        IgniteError::TileIsLava,
+    );
+
+    let stranger = h.new_player().await;
+    assert_ignite_error(
+        h.move_to(game_id, &stranger, 0, 1).await,
+        IgniteError::PlayerNotInGame,
+    );
+
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+    assert_ignite_error(
+        h.move_to(game_id, &bob, 3, 4).await,
+        IgniteError::PlayerEliminated,
+    );
+}
+
+#[tokio::test]
+async fn one_move_per_player_per_round() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+
+    // A rejected move doesn't use up the turn
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 2, 0).await,
+        IgniteError::InvalidMove,
+    );
+    h.move_to(game_id, &alice, 1, 0).await.unwrap();
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 2, 0).await,
+        IgniteError::AlreadyMoved,
+    );
+    // Other players keep their own turn
+    h.move_to(game_id, &bob, 3, 4).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert!(game.players().iter().all(|p| p.has_moved()));
+
+    // The collapse opens the next round
+    h.collapse(game_id, vec![]).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert!(game.players().iter().all(|p| !p.has_moved()));
+    h.move_to(game_id, &alice, 2, 0).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert_eq!((game.players[0].x, game.players[0].y), (2, 0));
+}
+
+#[tokio::test]
+async fn session_key_signs_moves() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let session = Keypair::new();
+
+    let ix = join_game_ix(
+        game_id,
+        &alice.key(),
+        &alice.token,
+        Some(session.pubkey()),
+        0,
+        0,
+        None,
+    );
+    h.send(&[ix], &[&alice.wallet, &session]).await.unwrap();
+    h.join(game_id, &bob, 4, 4).await.unwrap();
+    h.start(game_id).await.unwrap();
+
+    // The paying wallet isn't on the roster; its session key is
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 0).await,
+        IgniteError::PlayerNotInGame,
+    );
+    let ix = submit_move_ix(game_id, &session.pubkey(), None, 1, 0);
+    h.send(&[ix], &[&session]).await.unwrap();
+    assert_eq!(h.game(&game_id).await.players[0].x, 1);
+
+    // Payouts still go to the wallet that paid
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+    let authority = h.authority.pubkey();
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE + BUY_IN);
+}
+
+#[tokio::test]
+async fn cosigned_games_require_the_authority() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            require_cosign: true,
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 4, 4).await.unwrap();
+    h.start(game_id).await.unwrap();
+
+    assert_ignite_error(
+        h.move_to(game_id, &alice, 1, 0).await,
+        IgniteError::CosignRequired,
+    );
+
+    let imposter = Keypair::new();
+    let ix = submit_move_ix(game_id, &alice.key(), Some(imposter.pubkey()), 1, 0);
+    assert_ignite_error(
+        h.send(&[ix], &[&alice.wallet, &imposter]).await,
+        IgniteError::InvalidAuthority,
+    );
+
+    let ix = submit_move_ix(game_id, &alice.key(), Some(h.authority.pubkey()), 1, 0);
+    h.send(&[ix], &[&alice.wallet]).await.unwrap();
+    assert_eq!(h.game(&game_id).await.players[0].x, 1);
+}
+
+#[tokio::test]
+async fn trigger_collapse_is_authority_only() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 4, 4).await.unwrap();
+
+    assert_ignite_error(
+        h.collapse(game_id, vec![(2, 2)]).await,
+        IgniteError::GameNotActive,
+    );
+    h.start(game_id).await.unwrap();
+
+    let ix = trigger_collapse_ix(game_id, &alice.key(), vec![(4, 4)]);
+    assert_anchor_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+
+    h.collapse(game_id, vec![(2, 2)]).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert!(game.is_cracking(2 * 5 + 2));
+    assert!(game.players().iter().all(|p| p.is_alive()));
+}
+
+#[tokio::test]
+async fn declare_winner_validates_outcome_and_accounts() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+    let authority = h.authority.pubkey();
+
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::GameNotResolved);
+
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+
+    let ix = declare_winner_ix(game_id, &authority, &bob.token, &h.treasury);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::InvalidWinnerAccount);
+
+    let other_mint = h.create_mint().await;
+    let fake = h.create_token_account(&other_mint, &alice.key()).await;
+    let ix = declare_winner_ix(game_id, &authority, &fake, &h.treasury);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::InvalidMint);
+
+    let ix = declare_winner_ix(game_id, &alice.key(), &alice.token, &h.treasury);
+    assert_anchor_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+}
+
+#[tokio::test]
+async fn declare_draw_splits_pot_among_final_round() {
+    let mut h = Harness::new().await;
+    let buy_in = 1_000_001;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            buy_in,
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let carol = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 1, 1).await.unwrap();
+    h.join(game_id, &carol, 2, 2).await.unwrap();
+    h.start(game_id).await.unwrap();
+
+    h.collapse(game_id, vec![(2, 2)]).await.unwrap();
+    let authority = h.authority.pubkey();
+    let ix = declare_draw_ix(game_id, &authority, &[alice.token, bob.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::GameNotDrawn);
+
+    h.collapse_to_lava(game_id, vec![(0, 0), (1, 1)])
+        .await
+        .unwrap();
+
+    let ix = declare_draw_ix(game_id, &authority, &[alice.token, bob.token, carol.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::RefundAccountsMismatch,
+    );
+    let ix = declare_draw_ix(game_id, &authority, &[bob.token, alice.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::InvalidRefundAccount);
+
+    let ix = declare_draw_ix(game_id, &authority, &[alice.token, bob.token]);
+    h.send(&[ix], &[]).await.unwrap();
+
+    // Pot of 3_000_003 splits 1_500_001 each, odd unit to the earlier joiner
+    assert_eq!(
+        h.balance(&alice.token).await,
+        STARTING_BALANCE - buy_in + 1_500_002
+    );
+    assert_eq!(
+        h.balance(&bob.token).await,
+        STARTING_BALANCE - buy_in + 1_500_001
+    );
+    assert_eq!(h.balance(&carol.token).await, STARTING_BALANCE - buy_in);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, 0);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.status(), GameStatus::Draw);
+    assert_eq!(game.prize_pool, 0);
+    assert_eq!(game.winner(), None);
+}
+
+#[tokio::test]
+async fn no_collapse_after_everyone_is_eliminated() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+    h.collapse_to_lava(game_id, vec![(0, 0), (4, 4)])
+        .await
+        .unwrap();
+
+    // Another round would leave the finalists behind and strand the pot
+    assert_ignite_error(
+        h.collapse(game_id, vec![]).await,
+        IgniteError::GameAlreadyDecided,
+    );
+    assert_ignite_error(h.advance(game_id).await, IgniteError::GameAlreadyDecided);
+    assert_eq!(h.game(&game_id).await.final_round(), 2);
+
+    let authority = h.authority.pubkey();
+    let ix = declare_draw_ix(game_id, &authority, &[alice.token, bob.token]);
+    h.send(&[ix], &[]).await.unwrap();
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, 0);
+    let ix = close_game_ix(game_id, &authority, &authority);
+    h.send(&[ix], &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn seeded_game_only_accepts_the_schedule() {
+    let mut h = Harness::new().await;
+    let seed = [7u8; 32];
+    let params = InitializeGameParams {
+        seed_commitment: Some(hash(&seed).to_bytes()),
+        collapse_pattern: CollapsePattern::Random { count: 3 },
+        ..default_params()
+    };
+    let (authority, mint) = (h.authority.pubkey(), h.mint);
+    let ixs = create_game_ixs(
+        &authority,
+        &mint,
+        h.next_game_id(),
+        InitializeGameParams {
+            collapse_pattern: CollapsePattern::Random { count: 0 },
+            ..params.clone()
+        },
+    );
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::InvalidCollapseCount);
+
+    let game_id = h.init_game(params).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let (alice_entropy, bob_entropy) = ([1u8; 32], [2u8; 32]);
+    h.join_committed(game_id, &alice, 0, 0, alice_entropy)
+        .await
+        .unwrap();
+    h.join_committed(game_id, &bob, 4, 4, bob_entropy)
+        .await
+        .unwrap();
+    h.lock_lobby(game_id).await.unwrap();
+    h.set_paused(true).await.unwrap();
+    assert_ignite_error(
+        h.reveal_entropy(game_id, &alice, alice_entropy).await,
+        IgniteError::ProgramPaused,
+    );
+    h.set_paused(false).await.unwrap();
+    h.reveal_entropy(game_id, &alice, alice_entropy)
+        .await
+        .unwrap();
+    h.reveal_entropy(game_id, &bob, bob_entropy).await.unwrap();
+
+    // No peeking at the schedule before start tiles are locked in
+    let ix = reveal_seed_ix(game_id, &authority, seed);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::GameNotActive);
+    h.start(game_id).await.unwrap();
+
+    assert_ignite_error(
+        h.collapse(game_id, vec![(2, 2)]).await,
+        IgniteError::SeedNotRevealed,
+    );
+
+    let ix = reveal_seed_ix(game_id, &authority, [8u8; 32]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::SeedMismatch);
+    h.set_paused(true).await.unwrap();
+    let ix = reveal_seed_ix(game_id, &authority, seed);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::ProgramPaused);
+    h.set_paused(false).await.unwrap();
+    let timeout = default_params().inactivity_timeout;
+    h.advance_clock(timeout - 10).await;
+    let ix = reveal_seed_ix(game_id, &authority, seed);
+    h.send(&[ix], &[]).await.unwrap();
+    // Revealing the seed counts as the authority acting
+    h.advance_clock(20).await;
+    let ix = expire_game_ix(game_id, &authority, &[alice.token, bob.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::InactivityTimeoutNotReached,
+    );
+    let ix = reveal_seed_ix(game_id, &authority, seed);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::SeedAlreadyRevealed);
+
+    // The RNG mixes the authority seed with every player's entropy
+    let game = h.game(&game_id).await;
+    let rng = hashv(&[&seed, &alice_entropy, &bob_entropy]).to_bytes();
+    assert_eq!(game.seed(), Some(rng));
+    let round_one = collapse_schedule(&rng, 1, &game.lava, game.width, game.height, 3);
+
+    // The authority can't aim at a player, even with the right tile count
+    let mut aimed = round_one.clone();
+    aimed[0] = if round_one.contains(&(4, 4)) {
+        (0, 0)
+    } else {
+        (4, 4)
+    };
+    assert_ignite_error(
+        h.collapse(game_id, aimed).await,
+        IgniteError::CollapseScheduleMismatch,
+    );
+    let mut reordered = round_one.clone();
+    reordered.reverse();
+    assert_ignite_error(
+        h.collapse(game_id, reordered).await,
+        IgniteError::CollapseScheduleMismatch,
+    );
+
+    h.collapse(game_id, round_one.clone()).await.unwrap();
+
+    // Later rounds draw only from what is still safe
+    let game = h.game(&game_id).await;
+    let taken = std::array::from_fn(|i| game.lava[i] | game.cracking[i]);
+    let round_two = collapse_schedule(&rng, 2, &taken, game.width, game.height, 3);
+    assert_eq!(round_two.len(), 3);
+    for (x, y) in &round_two {
+        assert!(!round_one.contains(&(*x, *y)));
+        assert!(game.is_safe(*y as usize * 5 + *x as usize));
+    }
+    h.collapse(game_id, round_two).await.unwrap();
+    assert_eq!(h.game(&game_id).await.collapse_round, 2);
+}
+
+#[tokio::test]
+async fn entropy_must_be_committed_and_revealed_before_start() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            seed_commitment: Some(hash(&[7u8; 32]).to_bytes()),
+            collapse_pattern: CollapsePattern::Random { count: 1 },
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let carol = h.new_player().await;
+
+    assert_ignite_error(
+        h.join(game_id, &alice, 0, 0).await,
+        IgniteError::EntropyCommitmentRequired,
+    );
+    h.join_committed(game_id, &alice, 0, 0, [1u8; 32])
+        .await
+        .unwrap();
+    h.join_committed(game_id, &bob, 2, 2, [2u8; 32])
+        .await
+        .unwrap();
+    h.join_committed(game_id, &carol, 4, 4, [3u8; 32])
+        .await
+        .unwrap();
+
+    // Nothing is revealed, and nobody starts, until the lobby is locked
+    assert_ignite_error(
+        h.reveal_entropy(game_id, &alice, [1u8; 32]).await,
+        IgniteError::EntropyRevealsClosed,
+    );
+    assert_ignite_error(h.start(game_id).await, IgniteError::LobbyNotLocked);
+    h.lock_lobby(game_id).await.unwrap();
+
+    assert_ignite_error(
+        h.reveal_entropy(game_id, &alice, [9u8; 32]).await,
+        IgniteError::EntropyMismatch,
+    );
+    h.reveal_entropy(game_id, &alice, [1u8; 32]).await.unwrap();
+    assert_ignite_error(
+        h.reveal_entropy(game_id, &alice, [1u8; 32]).await,
+        IgniteError::EntropyAlreadyRevealed,
+    );
+    h.reveal_entropy(game_id, &bob, [2u8; 32]).await.unwrap();
+
+    // Carol never reveals and, under ForfeitRule::BuyIn, starts eliminated
+    let authority = h.authority.pubkey();
+    let ixs = [start_game_ix(game_id, &authority, &[carol.token])];
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::RefundAccountsMismatch);
+    h.start(game_id).await.unwrap();
+    assert_ignite_error(
+        h.reveal_entropy(game_id, &carol, [3u8; 32]).await,
+        IgniteError::EntropyRevealsClosed,
+    );
+
+    let game = h.game(&game_id).await;
+    assert!(game.players[0].is_alive() && game.players[1].is_alive());
+    assert!(!game.players[2].is_alive());
+    assert_eq!(game.prize_pool, 3 * BUY_IN);
+}
+
+#[tokio::test]
+async fn seat_forfeit_refunds_half_to_non_revealers() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            seed_commitment: Some(hash(&[7u8; 32]).to_bytes()),
+            collapse_pattern: CollapsePattern::Random { count: 1 },
+            forfeit_rule: ForfeitRule::Seat,
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let carol = h.new_player().await;
+    h.join_committed(game_id, &alice, 0, 0, [1u8; 32])
+        .await
+        .unwrap();
+    h.join_committed(game_id, &bob, 2, 2, [2u8; 32])
+        .await
+        .unwrap();
+    h.join_committed(game_id, &carol, 4, 4, [3u8; 32])
+        .await
+        .unwrap();
+    h.lock_lobby(game_id).await.unwrap();
+    h.reveal_entropy(game_id, &alice, [1u8; 32]).await.unwrap();
+    h.reveal_entropy(game_id, &carol, [3u8; 32]).await.unwrap();
+
+    let authority = h.authority.pubkey();
+    let ixs = [start_game_ix(
+        game_id,
+        &authority,
+        &[bob.token, carol.token],
+    )];
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::RefundAccountsMismatch);
+    let ixs = [start_game_ix(game_id, &authority, &[alice.token])];
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::InvalidRefundAccount);
+
+    // Bob starts out of the game, owed a refund that needn't fit here
+    h.start(game_id).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert!(!game.players[1].is_alive() && game.players[1].is_forfeited());
+    assert!(game.seat_refunds_pending());
+    assert_eq!(game.prize_pool, 3 * BUY_IN);
+
+    // Nothing settles until he is refunded
+    h.advance_clock(default_params().inactivity_timeout).await;
+    let caller = alice.key();
+    let ix = expire_game_ix(game_id, &caller, &[]);
+    assert_ignite_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        IgniteError::SeatRefundsPending,
+    );
+    let ix = pay_out_ix(game_id, 0, &[alice.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::InvalidRefundAccount);
+    let ix = pay_out_ix(game_id, 0, &[bob.token]);
+    h.send(&[ix], &[]).await.unwrap();
+    let ix = pay_out_ix(game_id, 0, &[bob.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::AlreadyPaidOut);
+
+    // Bob gets half his buy-in back; the other half stays in the pot
+    assert_eq!(
+        h.balance(&bob.token).await,
+        STARTING_BALANCE - BUY_IN + BUY_IN / 2
+    );
+    let pot = 3 * BUY_IN - BUY_IN / 2;
+    assert_eq!(h.game(&game_id).await.prize_pool, pot);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, pot);
+
+    let ix = expire_game_ix(game_id, &caller, &[alice.token, carol.token]);
+    h.send(&[ix], &[&alice.wallet]).await.unwrap();
+    for p in [&alice, &carol] {
+        assert_eq!(
+            h.balance(&p.token).await,
+            STARTING_BALANCE - BUY_IN + pot / 2
+        );
+    }
+}
+
+#[tokio::test]
+async fn seat_refunds_are_paid_in_batches() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            width: 64,
+            height: 64,
+            max_players: 64,
+            seed_commitment: Some(hash(&[7u8; 32]).to_bytes()),
+            collapse_pattern: CollapsePattern::Random { count: 1 },
+            forfeit_rule: ForfeitRule::Seat,
+            ..default_params()
+        })
+        .await;
+    let mut players = vec![];
+    for i in 0..64 {
+        let player = h.new_player().await;
+        h.join_committed(game_id, &player, i, i, [i; 32])
+            .await
+            .unwrap();
+        players.push(player);
+    }
+    h.lock_lobby(game_id).await.unwrap();
+    for i in 0..2 {
+        h.reveal_entropy(game_id, &players[i as usize], [i; 32])
+            .await
+            .unwrap();
+    }
+
+    // 62 forfeiters: start_game refunds the first 16, pay_out the rest
+    let refunds: Vec<_> = players[2..].iter().map(|p| p.token).collect();
+    let authority = h.authority.pubkey();
+    let ix = start_game_ix(game_id, &authority, &refunds[..16]);
+    h.send(&[ix], &[]).await.unwrap();
+    for start in (16..refunds.len()).step_by(16) {
+        let end = (start + 16).min(refunds.len());
+        let ix = pay_out_ix(game_id, start as u8, &refunds[start..end]);
+        h.send(&[ix], &[]).await.unwrap();
+    }
+
+    for token in &refunds {
+        assert_eq!(
+            h.balance(token).await,
+            STARTING_BALANCE - BUY_IN + BUY_IN / 2
+        );
+    }
+    let game = h.game(&game_id).await;
+    assert!(!game.seat_refunds_pending());
+    assert_eq!(game.prize_pool, 64 * BUY_IN - 62 * (BUY_IN / 2));
+}
+
+#[tokio::test]
+async fn forfeits_count_against_min_players() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            seed_commitment: Some(hash(&[7u8; 32]).to_bytes()),
+            collapse_pattern: CollapsePattern::Random { count: 1 },
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join_committed(game_id, &alice, 0, 0, [1u8; 32])
+        .await
+        .unwrap();
+    h.join_committed(game_id, &bob, 4, 4, [2u8; 32])
+        .await
+        .unwrap();
+    h.lock_lobby(game_id).await.unwrap();
+    h.reveal_entropy(game_id, &alice, [1u8; 32]).await.unwrap();
+
+    assert_ignite_error(h.start(game_id).await, IgniteError::NotEnoughPlayers);
+    h.reveal_entropy(game_id, &bob, [2u8; 32]).await.unwrap();
+    h.start(game_id).await.unwrap();
+}
+
+#[tokio::test]
+async fn joins_close_before_entropy_is_revealed() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            seed_commitment: Some(hash(&[7u8; 32]).to_bytes()),
+            collapse_pattern: CollapsePattern::Random { count: 1 },
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let mallory = h.new_player().await;
+    h.join_committed(game_id, &alice, 0, 0, [1u8; 32])
+        .await
+        .unwrap();
+
+    // The authority can't lock before min_players; others wait for the timeout
+    assert_ignite_error(h.lock_lobby(game_id).await, IgniteError::NotEnoughPlayers);
+    h.join_committed(game_id, &bob, 4, 4, [2u8; 32])
+        .await
+        .unwrap();
+    let ix = lock_lobby_ix(game_id, &mallory.key());
+    assert_ignite_error(
+        h.send(&[ix], &[&mallory.wallet]).await,
+        IgniteError::LobbyTimeoutNotReached,
+    );
+    h.lock_lobby(game_id).await.unwrap();
+    h.reveal_entropy(game_id, &alice, [1u8; 32]).await.unwrap();
+
+    // A late joiner can't pick their entropy or tile after seeing Alice's,
+    // and nobody can back out
+    assert_ignite_error(
+        h.join_committed(game_id, &mallory, 2, 2, [3u8; 32]).await,
+        IgniteError::GameNotJoinable,
+    );
+    let ix = leave_game_ix(game_id, &bob.key(), &bob.token);
+    assert_ignite_error(
+        h.send(&[ix], &[&bob.wallet]).await,
+        IgniteError::GameNotJoinable,
+    );
+
+    // Anyone may start once the lock has given players time to reveal
+    h.reveal_entropy(game_id, &bob, [2u8; 32]).await.unwrap();
+    let ix = start_game_ix(game_id, &mallory.key(), &[]);
+    assert_ignite_error(
+        h.send(&[ix], &[&mallory.wallet]).await,
+        IgniteError::LobbyTimeoutNotReached,
+    );
+    h.advance_clock(default_params().lobby_timeout).await;
+    let ix = start_game_ix(game_id, &mallory.key(), &[]);
+    h.send(&[ix], &[&mallory.wallet]).await.unwrap();
+    assert_eq!(h.game(&game_id).await.players().len(), 2);
+
+    // Unseeded games have nothing to reveal
+    let (game_id, _alice, _bob) = h.active_game().await;
+    assert_ignite_error(h.lock_lobby(game_id).await, IgniteError::NoSeedCommitted);
+}
+
+#[tokio::test]
+async fn reveal_seed_needs_a_commitment() {
+    let mut h = Harness::new().await;
+    let (game_id, _alice, _bob) = h.active_game().await;
+    let ix = reveal_seed_ix(game_id, &h.authority.pubkey(), [7u8; 32]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::NoSeedCommitted);
+}
+
+#[tokio::test]
+async fn expire_game_splits_pot_among_survivors() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let carol = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 2, 2).await.unwrap();
+    h.join(game_id, &carol, 4, 4).await.unwrap();
+
+    // Only active games can expire
+    let stranger = Keypair::new();
+    let ix = expire_game_ix(game_id, &stranger.pubkey(), &[]);
+    assert_ignite_error(
+        h.send(&[ix], &[&stranger]).await,
+        IgniteError::InvalidStatusTransition,
+    );
+
+    h.start(game_id).await.unwrap();
+    let timeout = default_params().inactivity_timeout;
+    h.advance_clock(timeout - 10).await;
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+
+    // The collapse restarted the inactivity clock
+    h.advance_clock(20).await;
+    let payouts = [alice.token, bob.token];
+    let ix = expire_game_ix(game_id, &stranger.pubkey(), &payouts);
+    assert_ignite_error(
+        h.send(&[ix], &[&stranger]).await,
+        IgniteError::InactivityTimeoutNotReached,
+    );
+
+    h.advance_clock(timeout).await;
+    let ix = expire_game_ix(game_id, &stranger.pubkey(), &[alice.token, carol.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[&stranger]).await,
+        IgniteError::InvalidRefundAccount,
+    );
+    let ix = expire_game_ix(game_id, &stranger.pubkey(), &payouts);
+    h.send(&[ix], &[&stranger]).await.unwrap();
+
+    let pot = 3 * BUY_IN;
+    for p in [&alice, &bob] {
+        assert_eq!(
+            h.balance(&p.token).await,
+            STARTING_BALANCE - BUY_IN + pot / 2
+        );
+    }
+    assert_eq!(h.balance(&carol.token).await, STARTING_BALANCE - BUY_IN);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.status(), GameStatus::Expired);
+    assert_eq!(game.prize_pool, 0);
+}
+
+#[tokio::test]
+async fn expire_game_with_no_survivors_pays_the_final_round() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, bob) = h.active_game().await;
+    h.collapse_to_lava(game_id, vec![(0, 0), (4, 4)])
+        .await
+        .unwrap();
+
+    h.advance_clock(default_params().inactivity_timeout).await;
+    let caller = alice.key();
+    let ix = expire_game_ix(game_id, &caller, &[alice.token, bob.token]);
+    h.send(&[ix], &[&alice.wallet]).await.unwrap();
+
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE);
+    assert_eq!(h.balance(&bob.token).await, STARTING_BALANCE);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, 0);
+}
+
+#[tokio::test]
+async fn close_game_returns_rent_to_receiver() {
+    let mut h = Harness::new().await;
+    let receiver = Keypair::new().pubkey();
+    let game_id = h
+        .init_game(InitializeGameParams {
+            rent_receiver: Some(receiver),
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 4, 4).await.unwrap();
+    h.start(game_id).await.unwrap();
+    let authority = h.authority.pubkey();
+
+    let ix = close_game_ix(game_id, &authority, &receiver);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::GameNotFinished);
+
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    h.send(&[ix], &[]).await.unwrap();
+
+    let ix = close_game_ix(game_id, &authority, &authority);
+    assert_anchor_error(h.send(&[ix], &[]).await, ErrorCode::ConstraintHasOne);
+
+    let (game, escrow) = (game_pda(&game_id), escrow_pda(&game_id));
+    let rent = h.lamports(&game).await + h.lamports(&escrow).await;
+    let ix = close_game_ix(game_id, &authority, &receiver);
+    h.send(&[ix], &[]).await.unwrap();
+
+    assert!(!h.exists(&game).await);
+    assert!(!h.exists(&escrow).await);
+    assert_eq!(h.lamports(&receiver).await, rent);
+}
+
+#[tokio::test]
+async fn close_game_requires_an_empty_escrow() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, _bob) = h.active_game().await;
+    let authority = h.authority.pubkey();
+    h.collapse_to_lava(game_id, vec![(4, 4)]).await.unwrap();
+    let ix = declare_winner_ix(game_id, &authority, &alice.token, &h.treasury);
+    h.send(&[ix], &[]).await.unwrap();
+
+    // Stray tokens sent straight to the vault block closing it
+    let mint = h.mint;
+    h.mint_to(&mint, &escrow_pda(&game_id), 1).await;
+    let ix = close_game_ix(game_id, &authority, &authority);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::EscrowNotEmpty);
+}
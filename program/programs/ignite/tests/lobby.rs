@@ -0,0 +1,482 @@
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use ignite::{CollapsePattern, GameState, GameStatus, IgniteError, InitializeGameParams};
+use solana_sdk::{program_pack::Pack, signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn initialize_game_creates_state_and_escrow() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+
+    let game = h.game(&game_id).await;
+    assert_eq!(game.game_id, game_id);
+    assert_eq!(game.authority, h.authority.pubkey());
+    assert_eq!(game.mint, h.mint);
+    assert_eq!(game.status(), GameStatus::Waiting);
+    assert_eq!((game.width, game.height), (5, 5));
+    assert!(game.lava.iter().all(|w| *w == 0));
+    assert!(game.players().is_empty());
+    assert_eq!(game.buy_in, BUY_IN);
+    assert_eq!((game.min_players, game.max_players), (2, 4));
+
+    let escrow = escrow_pda(&game_id);
+    let account = h
+        .ctx
+        .banks_client
+        .get_account(escrow)
+        .await
+        .unwrap()
+        .unwrap();
+    let vault = spl_token::state::Account::unpack(&account.data).unwrap();
+    assert_eq!(vault.mint, h.mint);
+    assert_eq!(vault.owner, escrow);
+    assert_eq!(vault.amount, 0);
+}
+
+#[tokio::test]
+async fn allocate_game_sizes_the_account_once() {
+    let mut h = Harness::new().await;
+    let authority = h.authority.pubkey();
+    let mint = h.mint;
+    let game_id = h.next_game_id();
+
+    let ixs = create_game_ixs(&authority, &mint, game_id, default_params());
+    h.send(&ixs, &[]).await.unwrap();
+    let ix = allocate_game_ix(&authority, game_id);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::GameAlreadyAllocated);
+
+    // One allocation isn't enough to initialize into
+    let game_id = h.next_game_id();
+    let ixs = [
+        allocate_game_ix(&authority, game_id),
+        initialize_game_ix(&authority, &mint, game_id, default_params()),
+    ];
+    assert_ignite_error(h.send(&ixs, &[]).await, IgniteError::GameNotAllocated);
+}
+
+#[tokio::test]
+async fn initialize_game_validates_params() {
+    let mut h = Harness::new().await;
+    let authority = h.authority.pubkey();
+    let mint = h.mint;
+
+    let cases: [(InitializeGameParams, IgniteError); 8] = [
+        (
+            InitializeGameParams {
+                width: 65,
+                ..default_params()
+            },
+            IgniteError::InvalidGridSize,
+        ),
+        (
+            InitializeGameParams {
+                min_players: 1,
+                ..default_params()
+            },
+            IgniteError::InvalidPlayerLimits,
+        ),
+        (
+            InitializeGameParams {
+                min_players: 5,
+                max_players: 4,
+                ..default_params()
+            },
+            IgniteError::InvalidPlayerLimits,
+        ),
+        (
+            InitializeGameParams {
+                max_players: 65,
+                ..default_params()
+            },
+            IgniteError::InvalidPlayerLimits,
+        ),
+        (
+            InitializeGameParams {
+                cancel_timeout: -1,
+                ..default_params()
+            },
+            IgniteError::InvalidTimeout,
+        ),
+        (
+            InitializeGameParams {
+                inactivity_timeout: -1,
+                ..default_params()
+            },
+            IgniteError::InvalidTimeout,
+        ),
+        (
+            InitializeGameParams {
+                lobby_timeout: 0,
+                ..default_params()
+            },
+            IgniteError::InvalidTimeout,
+        ),
+        (
+            InitializeGameParams {
+                inactivity_timeout: 0,
+                ..default_params()
+            },
+            IgniteError::InvalidTimeout,
+        ),
+    ];
+    for (params, error) in cases {
+        let game_id = h.next_game_id();
+        let ixs = create_game_ixs(&authority, &mint, game_id, params);
+        assert_ignite_error(h.send(&ixs, &[]).await, error);
+    }
+}
+
+#[tokio::test]
+async fn join_game_escrows_buy_in() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+
+    h.join(game_id, &alice, 1, 2).await.unwrap();
+
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE - BUY_IN);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, BUY_IN);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.prize_pool, BUY_IN);
+    assert_eq!(game.players().len(), 1);
+    let p = &game.players[0];
+    assert_eq!(
+        (p.pubkey, p.owner, p.x, p.y, p.is_alive()),
+        (alice.key(), alice.key(), 1, 2, true)
+    );
+    // Joining alone never starts the game
+    assert_eq!(game.status(), GameStatus::Waiting);
+}
+
+#[tokio::test]
+async fn join_game_with_session_key() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let session = Keypair::new();
+
+    let ix = join_game_ix(
+        game_id,
+        &alice.key(),
+        &alice.token,
+        Some(session.pubkey()),
+        0,
+        0,
+        None,
+    );
+    h.send(&[ix], &[&alice.wallet, &session]).await.unwrap();
+
+    let game = h.game(&game_id).await;
+    assert_eq!(game.players[0].pubkey, session.pubkey());
+    assert_eq!(game.players[0].owner, alice.key());
+
+    // The same session key cannot be enrolled by another wallet
+    let bob = h.new_player().await;
+    let ix = join_game_ix(
+        game_id,
+        &bob.key(),
+        &bob.token,
+        Some(session.pubkey()),
+        1,
+        1,
+        None,
+    );
+    assert_ignite_error(
+        h.send(&[ix], &[&bob.wallet, &session]).await,
+        IgniteError::AlreadyJoined,
+    );
+}
+
+#[tokio::test]
+async fn join_game_rejects_invalid_joins() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            max_players: 2,
+            ..default_params()
+        })
+        .await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    let carol = h.new_player().await;
+
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    assert_ignite_error(
+        h.join(game_id, &alice, 1, 1).await,
+        IgniteError::AlreadyJoined,
+    );
+    assert_ignite_error(h.join(game_id, &bob, 0, 0).await, IgniteError::TileOccupied);
+    assert_ignite_error(h.join(game_id, &bob, 0, 5).await, IgniteError::OutOfBounds);
+
+    // Paying with a token account for some other mint
+    let other_mint = h.create_mint().await;
+    let fake = h.create_token_account(&other_mint, &bob.key()).await;
+    h.mint_to(&other_mint, &fake, BUY_IN).await;
+    let ix = join_game_ix(game_id, &bob.key(), &fake, None, 1, 1, None);
+    assert_ignite_error(
+        h.send(&[ix], &[&bob.wallet]).await,
+        IgniteError::InvalidMint,
+    );
+
+    h.join(game_id, &bob, 1, 1).await.unwrap();
+    assert_ignite_error(h.join(game_id, &carol, 2, 2).await, IgniteError::GameFull);
+
+    h.start(game_id).await.unwrap();
+    let dave = h.new_player().await;
+    assert_ignite_error(
+        h.join(game_id, &dave, 3, 3).await,
+        IgniteError::GameNotJoinable,
+    );
+}
+
+#[tokio::test]
+async fn leave_game_refunds_buy_in() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 1, 1).await.unwrap();
+
+    let ix = leave_game_ix(game_id, &alice.key(), &alice.token);
+    h.send(&[ix], &[&alice.wallet]).await.unwrap();
+
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, BUY_IN);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.prize_pool, BUY_IN);
+    assert_eq!(game.players().len(), 1);
+    assert_eq!(game.players[0].pubkey, bob.key());
+}
+
+#[tokio::test]
+async fn leave_game_rejects_invalid_leaves() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 1, 1).await.unwrap();
+
+    let stranger = h.new_player().await;
+    let ix = leave_game_ix(game_id, &stranger.key(), &stranger.token);
+    assert_ignite_error(
+        h.send(&[ix], &[&stranger.wallet]).await,
+        IgniteError::PlayerNotInGame,
+    );
+
+    // Refund must go to the leaving player's own wallet
+    let ix = leave_game_ix(game_id, &alice.key(), &bob.token);
+    assert_ignite_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        IgniteError::InvalidRefundAccount,
+    );
+
+    let other_mint = h.create_mint().await;
+    let fake = h.create_token_account(&other_mint, &alice.key()).await;
+    let ix = leave_game_ix(game_id, &alice.key(), &fake);
+    assert_ignite_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        IgniteError::InvalidMint,
+    );
+
+    h.start(game_id).await.unwrap();
+    let ix = leave_game_ix(game_id, &alice.key(), &alice.token);
+    assert_ignite_error(
+        h.send(&[ix], &[&alice.wallet]).await,
+        IgniteError::GameNotJoinable,
+    );
+}
+
+#[tokio::test]
+async fn start_game_requires_min_players_and_authority_or_timeout() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            min_players: 3,
+            ..default_params()
+        })
+        .await;
+    let players = [
+        h.new_player().await,
+        h.new_player().await,
+        h.new_player().await,
+    ];
+
+    h.join(game_id, &players[0], 0, 0).await.unwrap();
+    h.join(game_id, &players[1], 1, 1).await.unwrap();
+    assert_ignite_error(h.start(game_id).await, IgniteError::NotEnoughPlayers);
+
+    h.join(game_id, &players[2], 2, 2).await.unwrap();
+    let caller = &players[0].wallet;
+    let ixs = [start_game_ix(game_id, &caller.pubkey(), &[])];
+    assert_ignite_error(
+        h.send(&ixs, &[caller]).await,
+        IgniteError::LobbyTimeoutNotReached,
+    );
+
+    h.advance_clock(default_params().lobby_timeout).await;
+    h.send(&ixs, &[caller]).await.unwrap();
+    assert_eq!(h.game(&game_id).await.status(), GameStatus::Active);
+
+    assert_ignite_error(h.start(game_id).await, IgniteError::InvalidStatusTransition);
+}
+
+#[tokio::test]
+async fn cancel_game_refunds_everyone() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 1, 1).await.unwrap();
+
+    let authority = h.authority.pubkey();
+    let ix = cancel_game_ix(game_id, &authority, &[alice.token, bob.token]);
+    h.send(&[ix], &[]).await.unwrap();
+
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE);
+    assert_eq!(h.balance(&bob.token).await, STARTING_BALANCE);
+    assert_eq!(h.balance(&escrow_pda(&game_id)).await, 0);
+    let game = h.game(&game_id).await;
+    assert_eq!(game.status(), GameStatus::Cancelled);
+    assert_eq!(game.prize_pool, 0);
+}
+
+#[tokio::test]
+async fn cancel_game_is_permissionless_after_timeout() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+
+    let ixs = [cancel_game_ix(game_id, &alice.key(), &[alice.token])];
+    assert_ignite_error(
+        h.send(&ixs, &[&alice.wallet]).await,
+        IgniteError::CancelTimeoutNotReached,
+    );
+
+    h.advance_clock(default_params().cancel_timeout).await;
+    h.send(&ixs, &[&alice.wallet]).await.unwrap();
+    assert_eq!(h.balance(&alice.token).await, STARTING_BALANCE);
+    assert_eq!(h.game(&game_id).await.status(), GameStatus::Cancelled);
+}
+
+#[tokio::test]
+async fn cancel_game_validates_refund_accounts() {
+    let mut h = Harness::new().await;
+    let game_id = h.init_game(default_params()).await;
+    let alice = h.new_player().await;
+    let bob = h.new_player().await;
+    h.join(game_id, &alice, 0, 0).await.unwrap();
+    h.join(game_id, &bob, 1, 1).await.unwrap();
+    let authority = h.authority.pubkey();
+
+    let ix = cancel_game_ix(game_id, &authority, &[alice.token, bob.token, bob.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::RefundAccountsMismatch,
+    );
+
+    let ix = cancel_game_ix(game_id, &authority, &[bob.token, alice.token]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::InvalidRefundAccount);
+
+    h.start(game_id).await.unwrap();
+    let ix = cancel_game_ix(game_id, &authority, &[alice.token, bob.token]);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::InvalidStatusTransition,
+    );
+}
+
+#[tokio::test]
+async fn full_lobby_fits_allocated_space() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            width: 64,
+            height: 64,
+            max_players: 64,
+            collapse_pattern: CollapsePattern::RingShrink,
+            ..default_params()
+        })
+        .await;
+
+    let account = h
+        .ctx
+        .banks_client
+        .get_account(game_pda(&game_id))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), GameState::SIZE);
+
+    for i in 0..64 {
+        let player = h.new_player().await;
+        h.join(game_id, &player, i, i).await.unwrap();
+    }
+    let game = h.game(&game_id).await;
+    assert_eq!(game.players().len(), 64);
+
+    // The outer ring takes the two corner players on the diagonal
+    h.start(game_id).await.unwrap();
+    h.advance(game_id).await.unwrap();
+    h.advance(game_id).await.unwrap();
+    let game = h.game(&game_id).await;
+    let fallen: Vec<usize> = (0..64).filter(|&i| !game.players[i].is_alive()).collect();
+    assert_eq!(fallen, vec![0, 63]);
+    assert!(game.is_lava(game.tile(63, 0).unwrap()));
+    assert!(game.is_cracking(game.tile(1, 1).unwrap()));
+}
+
+#[tokio::test]
+async fn full_lobby_refunds_in_batches() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            width: 64,
+            height: 64,
+            max_players: 64,
+            ..default_params()
+        })
+        .await;
+    let mut refunds = vec![];
+    for i in 0..64 {
+        let player = h.new_player().await;
+        h.join(game_id, &player, i, i).await.unwrap();
+        refunds.push(player.token);
+    }
+    let authority = h.authority.pubkey();
+
+    // pay_out only settles finished games
+    let ix = pay_out_ix(game_id, 0, &refunds[..16]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::GameNotFinished);
+
+    // cancel_game refunds what fits; pay_out works through the rest
+    let ix = cancel_game_ix(game_id, &authority, &refunds[..16]);
+    h.send(&[ix], &[]).await.unwrap();
+    let ix = pay_out_ix(game_id, 8, &refunds[8..24]);
+    assert_ignite_error(h.send(&[ix], &[]).await, IgniteError::AlreadyPaidOut);
+    let ix = pay_out_ix(game_id, 56, &refunds[56..]);
+    h.send(&[ix], &[]).await.unwrap();
+    let ix = pay_out_ix(game_id, 60, &refunds[56..]);
+    assert_ignite_error(
+        h.send(&[ix], &[]).await,
+        IgniteError::RefundAccountsMismatch,
+    );
+    for start in (16..56).step_by(8) {
+        let ix = pay_out_ix(game_id, start as u8, &refunds[start..start + 8]);
+        h.send(&[ix], &[]).await.unwrap();
+    }
+
+    for token in &refunds {
+        assert_eq!(h.balance(token).await, STARTING_BALANCE);
+    }
+    let game = h.game(&game_id).await;
+    assert_eq!(game.prize_pool, 0);
+    assert!(game.players().iter().all(|p| p.is_paid_out()));
+    let ix = close_game_ix(game_id, &authority, &authority);
+    h.send(&[ix], &[]).await.unwrap();
+}
@@ -0,0 +1,291 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use common::*;
+use ignite::{move_commitment, Direction, GameState, IgniteError, InitializeGameParams, MovePhase};
+
+/// A started sealed-move 5×5 game with a player on each of `tiles`.
+async fn sealed_game(h: &mut Harness, tiles: &[(u8, u8)]) -> ([u8; 16], Vec<Player>) {
+    let game_id = h
+        .init_game(InitializeGameParams {
+            sealed_moves: true,
+            max_players: 6,
+            ..default_params()
+        })
+        .await;
+    let mut players = vec![];
+    for &(x, y) in tiles {
+        let player = h.new_player().await;
+        h.join(game_id, &player, x, y).await.unwrap();
+        players.push(player);
+    }
+    h.start(game_id).await.unwrap();
+    (game_id, players)
+}
+
+fn positions(game: &GameState) -> Vec<(u8, u8)> {
+    game.players().iter().map(|p| (p.x, p.y)).collect()
+}
+
+#[tokio::test]
+async fn sealed_moves_commit_then_reveal() {
+    let mut h = Harness::new().await;
+    let (game_id, players) = sealed_game(&mut h, &[(0, 0), (4, 4), (2, 2)]).await;
+    let [alice, bob, carol] = &players[..] else {
+        unreachable!()
+    };
+
+    assert_ignite_error(
+        h.move_to(game_id, alice, 1, 0).await,
+        IgniteError::MovesSealed,
+    );
+    assert_ignite_error(
+        h.reveal_move(game_id, alice, Direction::Right).await,
+        IgniteError::WrongMovePhase,
+    );
+    h.commit_move(game_id, alice, Direction::Right)
+        .await
+        .unwrap();
+    assert_ignite_error(
+        h.commit_move(game_id, alice, Direction::Down).await,
+        IgniteError::AlreadyMoved,
+    );
+    h.commit_move(game_id, bob, Direction::Up).await.unwrap();
+
+    // Commits close; the board can't change until the moves resolve
+    h.open_reveals(game_id).await.unwrap();
+    assert_eq!(h.game(&game_id).await.move_phase(), MovePhase::Reveal);
+    assert_ignite_error(
+        h.commit_move(game_id, carol, Direction::Left).await,
+        IgniteError::WrongMovePhase,
+    );
+    assert_ignite_error(
+        h.collapse(game_id, vec![]).await,
+        IgniteError::WrongMovePhase,
+    );
+
+    assert_ignite_error(
+        h.reveal_move(game_id, alice, Direction::Down).await,
+        IgniteError::MoveMismatch,
+    );
+    assert_ignite_error(
+        h.reveal_move(game_id, carol, Direction::Left).await,
+        IgniteError::NoMoveCommitted,
+    );
+    h.reveal_move(game_id, alice, Direction::Right)
+        .await
+        .unwrap();
+    assert_ignite_error(
+        h.reveal_move(game_id, alice, Direction::Right).await,
+        IgniteError::MoveAlreadyRevealed,
+    );
+    // Nothing moves until every reveal is in
+    assert_eq!(positions(&h.game(&game_id).await), [(0, 0), (4, 4), (2, 2)]);
+
+    // Bob never reveals, so he stays put
+    h.resolve_moves(game_id).await.unwrap();
+    let game = h.game(&game_id).await;
+    assert_eq!(positions(&game), [(1, 0), (4, 4), (2, 2)]);
+    assert_eq!(game.move_phase(), MovePhase::Resolved);
+    assert!(game.players().iter().all(|p| p.revealed_move().is_none()));
+
+    // Still one move a round; the collapse opens the next
+    assert_ignite_error(
+        h.commit_move(game_id, alice, Direction::Right).await,
+        IgniteError::WrongMovePhase,
+    );
+    h.collapse(game_id, vec![]).await.unwrap();
+    h.commit_move(game_id, alice, Direction::Right)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn collisions_bounce_movers_back() {
+    let mut h = Harness::new().await;
+    let tiles = [(0, 0), (2, 0), (0, 2), (1, 2), (3, 2), (4, 2)];
+    let (game_id, players) = sealed_game(&mut h, &tiles).await;
+    let moves = [
+        // Both want (1,0)
+        Direction::Right,
+        Direction::Left,
+        // (1,2) and (3,2) contest (2,2), so (1,2) stays and blocks (0,2)
+        Direction::Right,
+        Direction::Right,
+        Direction::Left,
+        // Off the board: dropped
+        Direction::Right,
+    ];
+    for (player, &direction) in players.iter().zip(&moves) {
+        h.commit_move(game_id, player, direction).await.unwrap();
+    }
+    h.open_reveals(game_id).await.unwrap();
+    for (player, &direction) in players.iter().zip(&moves) {
+        h.reveal_move(game_id, player, direction).await.unwrap();
+    }
+    h.resolve_moves(game_id).await.unwrap();
+    assert_eq!(positions(&h.game(&game_id).await), tiles);
+
+    // Swapping places bounces; following a player out of their tile doesn't
+    h.collapse(game_id, vec![]).await.unwrap();
+    let moves = [
+        (2, Direction::Right),
+        (3, Direction::Left),
+        (4, Direction::Left),
+        (5, Direction::Left),
+    ];
+    for &(i, direction) in &moves {
+        h.commit_move(game_id, &players[i], direction)
+            .await
+            .unwrap();
+    }
+    h.open_reveals(game_id).await.unwrap();
+    for &(i, direction) in &moves {
+        h.reveal_move(game_id, &players[i], direction)
+            .await
+            .unwrap();
+    }
+    h.resolve_moves(game_id).await.unwrap();
+    let expected = [(0, 0), (2, 0), (0, 2), (1, 2), (2, 2), (3, 2)];
+    assert_eq!(positions(&h.game(&game_id).await), expected);
+}
+
+#[tokio::test]
+async fn a_full_row_resolves_in_one_pass() {
+    let mut h = Harness::new().await;
+    let game_id = h
+        .init_game(InitializeGameParams {
+            width: 64,
+            height: 2,
+            max_players: 64,
+            sealed_moves: true,
+            ..default_params()
+        })
+        .await;
+    let mut players = vec![];
+    for x in 0..64 {
+        let player = h.new_player().await;
+        h.join(game_id, &player, x, 0).await.unwrap();
+        players.push(player);
+    }
+    h.start(game_id).await.unwrap();
+
+    // Everyone steps left except (32,0), which steps down. The front
+    // player walks off the board, so the 31 behind it bounce one after
+    // another; the 31 behind (32,0) follow it out.
+    let direction = |x: usize| {
+        if x == 32 {
+            Direction::Down
+        } else {
+            Direction::Left
+        }
+    };
+    for (x, player) in players.iter().enumerate() {
+        h.commit_move(game_id, player, direction(x)).await.unwrap();
+    }
+    h.open_reveals(game_id).await.unwrap();
+    for (x, player) in players.iter().enumerate() {
+        h.reveal_move(game_id, player, direction(x)).await.unwrap();
+    }
+    h.resolve_moves(game_id).await.unwrap();
+
+    let expected: Vec<(u8, u8)> = (0..64)
+        .map(|x| match x {
+            0..=31 => (x, 0),
+            32 => (32, 1),
+            _ => (x - 1, 0),
+        })
+        .collect();
+    assert_eq!(positions(&h.game(&game_id).await), expected);
+}
+
+#[tokio::test]
+async fn collapse_waits_for_the_round_to_resolve() {
+    let mut h = Harness::new().await;
+    let (game_id, players) = sealed_game(&mut h, &[(0, 0), (4, 4)]).await;
+    let [alice, bob] = &players[..] else {
+        unreachable!()
+    };
+
+    // Not while commits are open, even with none made
+    assert_ignite_error(
+        h.collapse(game_id, vec![]).await,
+        IgniteError::WrongMovePhase,
+    );
+    h.commit_move(game_id, alice, Direction::Right)
+        .await
+        .unwrap();
+    h.commit_move(game_id, bob, Direction::Up).await.unwrap();
+    assert_ignite_error(
+        h.collapse(game_id, vec![]).await,
+        IgniteError::WrongMovePhase,
+    );
+    h.open_reveals(game_id).await.unwrap();
+    h.reveal_move(game_id, alice, Direction::Right)
+        .await
+        .unwrap();
+    h.resolve_moves(game_id).await.unwrap();
+    h.collapse(game_id, vec![]).await.unwrap();
+    assert_eq!(h.game(&game_id).await.move_phase(), MovePhase::Commit);
+
+    // Bob's unrevealed commitment doesn't carry into the next round
+    h.open_reveals(game_id).await.unwrap();
+    assert_ignite_error(
+        h.reveal_move(game_id, bob, Direction::Up).await,
+        IgniteError::NoMoveCommitted,
+    );
+    h.resolve_moves(game_id).await.unwrap();
+    assert_eq!(positions(&h.game(&game_id).await), [(1, 0), (4, 4)]);
+}
+
+#[tokio::test]
+async fn round_steps_restart_the_inactivity_clock() {
+    let mut h = Harness::new().await;
+    let (game_id, players) = sealed_game(&mut h, &[(0, 0), (4, 4)]).await;
+    let timeout = default_params().inactivity_timeout;
+    let stranger = players[0].key();
+    let payouts = [players[0].token, players[1].token];
+
+    h.advance_clock(timeout - 10).await;
+    h.open_reveals(game_id).await.unwrap();
+    h.advance_clock(timeout - 10).await;
+    h.resolve_moves(game_id).await.unwrap();
+    h.advance_clock(timeout - 10).await;
+    let ix = expire_game_ix(game_id, &stranger, &payouts);
+    assert_ignite_error(
+        h.send(&[ix], &[&players[0].wallet]).await,
+        IgniteError::InactivityTimeoutNotReached,
+    );
+}
+
+#[tokio::test]
+async fn sealed_instructions_need_a_sealed_game() {
+    let mut h = Harness::new().await;
+    let (game_id, alice, _bob) = h.active_game().await;
+    assert_ignite_error(
+        h.commit_move(game_id, &alice, Direction::Right).await,
+        IgniteError::MovesNotSealed,
+    );
+    assert_ignite_error(h.open_reveals(game_id).await, IgniteError::MovesNotSealed);
+
+    // The authority, not a player, closes commits and resolves
+    let (game_id, players) = sealed_game(&mut h, &[(0, 0), (4, 4)]).await;
+    let ix = open_reveals_ix(game_id, &players[0].key());
+    assert_anchor_error(
+        h.send(&[ix], &[&players[0].wallet]).await,
+        ErrorCode::ConstraintHasOne,
+    );
+
+    // A commitment only opens for the salt it was made with
+    let commitment = move_commitment(Direction::Up, &[1; 32]);
+    let ix = commit_move_ix(game_id, &players[1].key(), None, commitment);
+    h.send(&[ix], &[&players[1].wallet]).await.unwrap();
+    h.open_reveals(game_id).await.unwrap();
+    let ix = reveal_move_ix(game_id, &players[1].key(), Direction::Up, [2; 32]);
+    assert_ignite_error(
+        h.send(&[ix], &[&players[1].wallet]).await,
+        IgniteError::MoveMismatch,
+    );
+    let ix = reveal_move_ix(game_id, &players[1].key(), Direction::Up, [1; 32]);
+    h.send(&[ix], &[&players[1].wallet]).await.unwrap();
+}
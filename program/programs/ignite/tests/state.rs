@@ -0,0 +1,40 @@
+//! GameState guards that no instruction can trip today, checked directly
+//! on an in-memory game.
+
+use bytemuck::Zeroable;
+use ignite::{GameState, GameStatus, IgniteError, PlayerState};
+
+#[test]
+fn roster_holds_at_most_max_players() {
+    let mut game = GameState::zeroed();
+    for _ in 0..game.players.len() {
+        game.add_player(PlayerState::zeroed()).unwrap();
+    }
+    assert_eq!(
+        game.add_player(PlayerState::zeroed()),
+        Err(IgniteError::RosterCapacityExceeded.into())
+    );
+}
+
+#[test]
+fn split_pot_rejects_a_rake_above_the_pot() {
+    let mut game = GameState::zeroed();
+    game.prize_pool = 1_000;
+    game.fee_bps = 250;
+    assert_eq!(game.split_pot(), Ok((25, 975)));
+
+    game.fee_bps = 20_000;
+    assert_eq!(game.split_pot(), Err(IgniteError::MathOverflow.into()));
+}
+
+#[test]
+fn a_draw_needs_finalists() {
+    let mut game = GameState::zeroed();
+    game.status = GameStatus::Draw.into();
+    assert_eq!(game.finalists(), Err(IgniteError::NoFinalists.into()));
+
+    let mut player = PlayerState::zeroed();
+    player.alive = 1;
+    game.add_player(player).unwrap();
+    assert_eq!(game.finalists(), Err(IgniteError::GameNotDrawn.into()));
+}
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID, createMint, getAccount } from '@solana/spl-token';
 import { assert } from 'chai';
 
 // @ts-ignore
@@ -12,6 +13,40 @@ describe('ignite', () => {
 
   const program = anchor.workspace.Ignite as Program<Ignite>;
   const authority = provider.wallet as anchor.Wallet;
+  let mint: PublicKey;
+
+  const [configPda] = PublicKey.findProgramAddressSync([Buffer.from('config')], program.programId);
+
+  before(async () => {
+    // Mock USDC: 6 decimals, authority is the provider wallet
+    mint = await createMint(provider.connection, authority.payer, authority.publicKey, null, 6);
+
+    const params = {
+      feeBps: 0,
+      treasury: authority.publicKey,
+      authorities: [authority.publicKey],
+      allowedMints: [mint],
+      minBuyIn: new anchor.BN(1),
+      maxBuyIn: new anchor.BN(100_000_000),
+    };
+
+    // Config is a singleton; later runs point it at this run's fresh mint
+    if (!(await provider.connection.getAccountInfo(configPda))) {
+      await program.methods
+        .initializeConfig(params)
+        .accounts({
+          config: configPda,
+          admin: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    } else {
+      await program.methods
+        .updateConfig(params)
+        .accounts({ config: configPda, admin: authority.publicKey })
+        .rpc();
+    }
+  });
 
   function uuidToBytes(uuid: string): Buffer {
     return Buffer.from(uuid.replace(/-/g, ''), 'hex');
@@ -23,7 +58,7 @@ describe('ignite', () => {
     return [raw, buf, Array.from(buf)];
   }
 
-  it('initialize_game creates a GameState PDA', async () => {
+  it('initialize_game creates a GameState PDA and escrow vault', async () => {
     const [_uuid, gameIdBuf, gameIdArr] = makeGameId();
 
     const [gameStatePda] = PublicKey.findProgramAddressSync(
@@ -35,21 +70,62 @@ describe('ignite', () => {
       program.programId
     );
 
+    const allocate = await program.methods
+      .allocateGame(gameIdArr as unknown as number[] & { length: 16 })
+      .accounts({
+        gameState: gameStatePda,
+        config: configPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .instruction();
+
     await program.methods
-      .initializeGame(gameIdArr as unknown as number[] & { length: 16 }, new anchor.BN(50000), 10)
+      .initializeGame(gameIdArr as unknown as number[] & { length: 16 }, {
+        buyIn: new anchor.BN(50000),
+        width: 10,
+        height: 10,
+        mask: null,
+        requireCosign: true,
+        cancelTimeout: new anchor.BN(3600),
+        minPlayers: 2,
+        maxPlayers: 10,
+        lobbyTimeout: new anchor.BN(600),
+        inactivityTimeout: new anchor.BN(300),
+        seedCommitment: null,
+        collapsePattern: { manual: {} },
+        forfeitRule: { buyIn: {} },
+        rentReceiver: null,
+        sealedMoves: false,
+      })
       .accounts({
         gameState: gameStatePda,
         escrowVault: escrowPda,
+        config: configPda,
+        mint,
         authority: authority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
+      // GameState is over 10 KiB, so allocate_game has to grow it twice
+      .preInstructions([allocate, allocate])
       .rpc();
 
     const gameState = await program.account.gameState.fetch(gameStatePda);
-    assert.equal(gameState.status, 0, 'status should be waiting (0)');
-    assert.equal(gameState.gridSize, 10);
+    assert.deepEqual(gameState.status, { waiting: {} }, 'status should be waiting');
+    assert.equal(gameState.width, 10);
+    assert.equal(gameState.height, 10);
     assert.equal(gameState.buyIn.toNumber(), 50000);
-    assert.equal(gameState.players.length, 0);
+    assert.equal(gameState.playerCount, 0);
+    assert.ok(gameState.mint.equals(mint));
+    assert.equal(gameState.requireCosign, 1);
+    assert.equal(gameState.minPlayers, 2);
+    assert.equal(gameState.maxPlayers, 10);
+
+    const vault = await getAccount(provider.connection, escrowPda);
+    assert.ok(vault.mint.equals(mint));
+    assert.ok(vault.owner.equals(escrowPda), 'escrow vault should be owned by its own PDA');
+    assert.equal(vault.amount, BigInt(0));
     console.log('✓ GameState PDA initialized:', gameStatePda.toBase58());
   });
 });